@@ -0,0 +1,3 @@
+-- Add down migration script here
+-- Enum value 'sse' cannot be dropped from fetch_api_type
+ALTER TABLE fetch_api DROP COLUMN IF EXISTS last_event_id;
@@ -0,0 +1,5 @@
+-- Add up migration script here
+ALTER TYPE fetch_api_type ADD VALUE IF NOT EXISTS 'sse';
+
+-- Resume point for sse fetch (Last-Event-ID)
+ALTER TABLE fetch_api ADD COLUMN last_event_id TEXT;
@@ -0,0 +1,6 @@
+-- Add up migration script here
+ALTER TYPE fetch_api_type ADD VALUE IF NOT EXISTS 'tcp';
+ALTER TYPE fetch_api_type ADD VALUE IF NOT EXISTS 'udp';
+
+-- Executor options by fetch type
+ALTER TABLE fetch_api ADD COLUMN options JSONB DEFAULT '{}'::jsonb;
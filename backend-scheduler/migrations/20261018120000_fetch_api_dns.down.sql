@@ -0,0 +1,2 @@
+-- Add down migration script here
+-- Enum value 'dns' cannot be dropped from fetch_api_type
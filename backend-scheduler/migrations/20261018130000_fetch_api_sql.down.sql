@@ -0,0 +1,2 @@
+-- Add down migration script here
+-- Enum value 'sql' cannot be dropped from fetch_api_type
@@ -0,0 +1,5 @@
+-- Add down migration script here
+-- Enum value 'head' and 'options' cannot be dropped from fetch_api_method
+ALTER TABLE fetch_api DROP COLUMN IF EXISTS body_mode;
+ALTER TABLE fetch_api DROP COLUMN IF EXISTS query;
+DROP TYPE IF EXISTS fetch_api_body_mode;
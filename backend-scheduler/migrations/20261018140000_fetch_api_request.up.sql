@@ -0,0 +1,14 @@
+-- Add up migration script here
+ALTER TYPE fetch_api_method ADD VALUE IF NOT EXISTS 'head';
+ALTER TYPE fetch_api_method ADD VALUE IF NOT EXISTS 'options';
+
+CREATE TYPE fetch_api_body_mode AS ENUM (
+    'raw',
+    'json',
+    'form',
+    'multipart'
+);
+
+-- Query params map & body encoding for http request
+ALTER TABLE fetch_api ADD COLUMN query JSONB;
+ALTER TABLE fetch_api ADD COLUMN body_mode fetch_api_body_mode NOT NULL DEFAULT 'raw';
@@ -0,0 +1,3 @@
+-- Add up migration script here
+-- Run metadata (redirect chain etc)
+ALTER TABLE fetch_api_data ADD COLUMN meta JSONB;
@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE fetch_api DROP COLUMN IF EXISTS credential_id;
+DROP TABLE IF EXISTS fetch_api_credential;
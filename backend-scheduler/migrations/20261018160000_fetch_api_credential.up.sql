@@ -0,0 +1,29 @@
+-- Add up migration script here
+-- CREATE TABLE fetch_api_credential (mTLS client cert & private CA)
+CREATE TABLE fetch_api_credential (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    client_cert TEXT,
+    client_key TEXT,
+    ca_bundle TEXT,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT fk_fetch_credential_user
+        FOREIGN KEY (user_id)
+        REFERENCES users(id)
+        ON DELETE CASCADE
+);
+
+CREATE TRIGGER trg_set_timestamp_fetch_credential
+BEFORE UPDATE ON fetch_api_credential
+FOR EACH ROW
+EXECUTE PROCEDURE update_updated_at_column();
+
+ALTER TABLE fetch_api ADD COLUMN credential_id INTEGER;
+ALTER TABLE fetch_api ADD CONSTRAINT fk_fetch_credential
+    FOREIGN KEY (credential_id)
+    REFERENCES fetch_api_credential(id)
+    ON DELETE SET NULL;
+
+CREATE INDEX idx_fetch_api_credential_id ON fetch_api(credential_id);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE fetch_api_data DROP COLUMN IF EXISTS content_type;
+ALTER TABLE fetch_api_data DROP COLUMN IF EXISTS response_raw;
@@ -0,0 +1,4 @@
+-- Add up migration script here
+-- Original body bytes (binary or non utf-8) & response content type
+ALTER TABLE fetch_api_data ADD COLUMN response_raw BYTEA;
+ALTER TABLE fetch_api_data ADD COLUMN content_type TEXT;
@@ -0,0 +1,5 @@
+-- Add down migration script here
+ALTER TABLE fetch_api_execute DROP COLUMN IF EXISTS timezone;
+ALTER TABLE fetch_api_execute DROP COLUMN IF EXISTS cron;
+-- Enum value 'cron' can not be dropped, fallback to default interval
+UPDATE fetch_api_execute SET type = 'minutes' WHERE type = 'cron';
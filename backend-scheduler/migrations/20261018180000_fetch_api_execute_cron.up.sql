@@ -0,0 +1,5 @@
+-- Add up migration script here
+ALTER TYPE execute_type ADD VALUE IF NOT EXISTS 'cron';
+
+ALTER TABLE fetch_api_execute ADD COLUMN cron TEXT;
+ALTER TABLE fetch_api_execute ADD COLUMN timezone TEXT;
@@ -0,0 +1,6 @@
+-- Add down migration script here
+ALTER TABLE fetch_api_execute DROP COLUMN IF EXISTS calendar_id;
+ALTER TABLE fetch_api_execute DROP COLUMN IF EXISTS rrule;
+DROP TABLE IF EXISTS fetch_api_calendar;
+-- Enum value 'rrule' can not be dropped, fallback to default interval
+UPDATE fetch_api_execute SET type = 'minutes' WHERE type = 'rrule';
@@ -0,0 +1,30 @@
+-- Add up migration script here
+ALTER TYPE execute_type ADD VALUE IF NOT EXISTS 'rrule';
+
+-- CREATE TABLE fetch_api_calendar (holiday dates imported from .ics)
+CREATE TABLE fetch_api_calendar (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    dates DATE[] NOT NULL DEFAULT '{}',
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT fk_fetch_calendar_user
+        FOREIGN KEY (user_id)
+        REFERENCES users(id)
+        ON DELETE CASCADE
+);
+
+CREATE TRIGGER trg_set_timestamp_fetch_calendar
+BEFORE UPDATE ON fetch_api_calendar
+FOR EACH ROW
+EXECUTE PROCEDURE update_updated_at_column();
+
+ALTER TABLE fetch_api_execute ADD COLUMN rrule TEXT;
+ALTER TABLE fetch_api_execute ADD COLUMN calendar_id INTEGER;
+ALTER TABLE fetch_api_execute ADD CONSTRAINT fk_execute_calendar
+    FOREIGN KEY (calendar_id)
+    REFERENCES fetch_api_calendar(id)
+    ON DELETE SET NULL;
+
+CREATE INDEX idx_fetch_api_execute_calendar_id ON fetch_api_execute(calendar_id);
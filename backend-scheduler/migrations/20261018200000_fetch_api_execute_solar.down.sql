@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE fetch_api_execute DROP COLUMN IF EXISTS solar;
+-- Enum value 'solar' can not be dropped, fallback to default interval
+UPDATE fetch_api_execute SET type = 'minutes' WHERE type = 'solar';
@@ -0,0 +1,5 @@
+-- Add up migration script here
+ALTER TYPE execute_type ADD VALUE IF NOT EXISTS 'solar';
+
+-- Solar / prayer time anchor: event, latitude, longitude, method, offset
+ALTER TABLE fetch_api_execute ADD COLUMN solar JSONB;
@@ -0,0 +1,6 @@
+-- Add down migration script here
+ALTER TABLE fetch_api DROP COLUMN IF EXISTS run_count;
+ALTER TABLE fetch_api_execute DROP COLUMN IF EXISTS max_runs;
+ALTER TABLE fetch_api_execute DROP COLUMN IF EXISTS ends_at;
+ALTER TABLE fetch_api_execute DROP COLUMN IF EXISTS starts_at;
+ALTER TABLE fetch_api_execute DROP COLUMN IF EXISTS run_at;
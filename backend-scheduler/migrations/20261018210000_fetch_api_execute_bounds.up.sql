@@ -0,0 +1,9 @@
+-- Add up migration script here
+-- Absolute one-shot run & repeat window
+ALTER TABLE fetch_api_execute ADD COLUMN run_at TIMESTAMPTZ;
+ALTER TABLE fetch_api_execute ADD COLUMN starts_at TIMESTAMPTZ;
+ALTER TABLE fetch_api_execute ADD COLUMN ends_at TIMESTAMPTZ;
+ALTER TABLE fetch_api_execute ADD COLUMN max_runs BIGINT;
+
+-- Runs done by fetch with current execute
+ALTER TABLE fetch_api ADD COLUMN run_count BIGINT NOT NULL DEFAULT 0;
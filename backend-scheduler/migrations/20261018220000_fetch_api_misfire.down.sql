@@ -0,0 +1,6 @@
+-- Add down migration script here
+ALTER TABLE fetch_api DROP COLUMN IF EXISTS missed_count;
+ALTER TABLE fetch_api DROP COLUMN IF EXISTS scheduled_at;
+ALTER TABLE fetch_api_execute DROP COLUMN IF EXISTS misfire_limit;
+ALTER TABLE fetch_api_execute DROP COLUMN IF EXISTS misfire_policy;
+DROP TYPE IF EXISTS misfire_policy;
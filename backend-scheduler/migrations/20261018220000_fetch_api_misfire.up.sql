@@ -0,0 +1,13 @@
+-- Add up migration script here
+CREATE TYPE misfire_policy AS ENUM (
+    'skip',
+    'run_once',
+    'run_all'
+);
+
+ALTER TABLE fetch_api_execute ADD COLUMN misfire_policy misfire_policy NOT NULL DEFAULT 'run_once';
+ALTER TABLE fetch_api_execute ADD COLUMN misfire_limit INTEGER;
+
+-- Planned time of pending job & missed slot counter
+ALTER TABLE fetch_api ADD COLUMN scheduled_at TIMESTAMPTZ;
+ALTER TABLE fetch_api ADD COLUMN missed_count BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,3 @@
+-- Add up migration script here
+ALTER TABLE fetch_api ADD COLUMN retry JSONB;
+ALTER TABLE fetch_api_execute ADD COLUMN retry JSONB;
@@ -0,0 +1,5 @@
+-- Add up migration script here
+ALTER TABLE fetch_api ADD COLUMN assertions JSONB;
+
+-- Assertion report of each run
+ALTER TABLE fetch_api_data ADD COLUMN assertions JSONB;
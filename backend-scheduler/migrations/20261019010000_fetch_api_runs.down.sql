@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS fetch_api_runs;
+DROP TYPE IF EXISTS run_outcome;
@@ -0,0 +1,31 @@
+-- Add up migration script here
+CREATE TYPE run_outcome AS ENUM (
+    'success',
+    'failed',
+    'timeout',
+    'skipped'
+);
+
+CREATE TABLE IF NOT EXISTS fetch_api_runs (
+    id SERIAL PRIMARY KEY,
+    fetch_id INTEGER NOT NULL,
+    job_id TEXT,
+    planned_at TIMESTAMPTZ,
+    started_at TIMESTAMPTZ NOT NULL,
+    finished_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    attempt INTEGER NOT NULL DEFAULT 1,
+    outcome run_outcome NOT NULL,
+    error TEXT,
+    data_id INTEGER,
+
+    CONSTRAINT fk_run_fetch
+        FOREIGN KEY (fetch_id)
+        REFERENCES fetch_api(id)
+        ON DELETE CASCADE,
+    CONSTRAINT fk_run_data
+        FOREIGN KEY (data_id)
+        REFERENCES fetch_api_data(id)
+        ON DELETE SET NULL
+);
+
+CREATE INDEX idx_fetch_api_runs_fetch_started ON fetch_api_runs(fetch_id, started_at DESC);
@@ -0,0 +1,9 @@
+-- Add down migration script here
+-- Put connection string back to header set of sql fetch
+UPDATE fetch_api_header hd
+SET headers = COALESCE(hd.headers, '{}'::jsonb) || jsonb_build_object('database_url', c.database_url)
+FROM fetch_api f
+JOIN fetch_api_credential c ON c.id = f.credential_id
+WHERE f.header_id = hd.id AND f.type = 'sql' AND c.database_url IS NOT NULL;
+
+ALTER TABLE fetch_api_credential DROP COLUMN IF EXISTS database_url;
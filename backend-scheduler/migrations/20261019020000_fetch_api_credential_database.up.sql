@@ -0,0 +1,24 @@
+-- Add up migration script here
+-- Sql fetch connection string kept in credential (never returned), no longer in header set
+ALTER TABLE fetch_api_credential ADD COLUMN database_url TEXT;
+
+-- Move database_url of header sets used by sql fetch into a credential
+DO $$
+DECLARE
+    h RECORD;
+    c_id INTEGER;
+BEGIN
+    FOR h IN
+        SELECT DISTINCT hd.id, hd.user_id, hd.name, hd.headers->>'database_url' AS database_url
+        FROM fetch_api_header hd
+        JOIN fetch_api f ON f.header_id = hd.id
+        WHERE f.type = 'sql' AND hd.headers ? 'database_url'
+    LOOP
+        INSERT INTO fetch_api_credential (user_id, name, database_url)
+        VALUES (h.user_id, h.name, h.database_url)
+        RETURNING id INTO c_id;
+
+        UPDATE fetch_api SET credential_id = c_id WHERE header_id = h.id AND type = 'sql';
+        UPDATE fetch_api_header SET headers = headers - 'database_url' WHERE id = h.id;
+    END LOOP;
+END $$;
@@ -12,7 +12,12 @@ pub struct Config {
     pub migrate: bool,
     pub log_level: Level,
     pub min_job_interval: u64,
+    pub http_timeout: u64,
     pub ws_timeout: u64,
+    pub mqtt_timeout: u64,
+    pub sse_timeout: u64,
+    pub socket_timeout: u64,
+    pub max_body_bytes: usize,
     pub root_username: String,
     pub root_email: String,
     pub root_password: String,
@@ -34,7 +39,12 @@ impl Config {
         let migrate = env::var("MIGRATIONS").unwrap_or("false".to_string()).to_lowercase().parse::<bool>().unwrap_or(false);
         let log_level_str = env::var("LOG_LEVEL").unwrap_or_else(|_| "INFO".to_string()).to_uppercase();
         let min_job_interval = env::var("MIN_JOB_INTERVAL").ok().and_then(|v| v.parse::<u64>().ok()).map(|v| v.max(1)).unwrap_or(10);
+        let http_timeout = env::var("HTTP_TIMEOUT").ok().and_then(|v| v.parse::<u64>().ok()).map(|v| v.max(1)).unwrap_or(10);
         let ws_timeout = env::var("WS_TIMEOUT").ok().and_then(|v| v.parse::<u64>().ok()).map(|v| v.max(1)).unwrap_or(10);
+        let mqtt_timeout = env::var("MQTT_TIMEOUT").ok().and_then(|v| v.parse::<u64>().ok()).map(|v| v.max(1)).unwrap_or(10);
+        let sse_timeout = env::var("SSE_TIMEOUT").ok().and_then(|v| v.parse::<u64>().ok()).map(|v| v.max(1)).unwrap_or(10);
+        let socket_timeout = env::var("SOCKET_TIMEOUT").ok().and_then(|v| v.parse::<u64>().ok()).map(|v| v.max(1)).unwrap_or(10);
+        let max_body_bytes = env::var("MAX_BODY_BYTES").ok().and_then(|v| v.parse::<usize>().ok()).map(|v| v.max(1)).unwrap_or(10 * 1024 * 1024);
         let root_username = env::var("ROOT_USERNAME").expect("ROOT_USERNAME required");
         let root_email = env::var("ROOT_EMAIL").expect("ROOT_EMAIL required");
         let root_password = env::var("ROOT_PASSWORD").expect("ROOT_PASSWORD required");
@@ -57,7 +67,12 @@ impl Config {
             migrate,
             log_level,
             min_job_interval,
+            http_timeout,
             ws_timeout,
+            mqtt_timeout,
+            sse_timeout,
+            socket_timeout,
+            max_body_bytes,
             root_username,
             root_email,
             root_password,
@@ -1,8 +1,8 @@
-use axum::{http::Uri, response::IntoResponse};
+use axum::{http::{HeaderName, HeaderValue, Uri, header::{CONTENT_DISPOSITION, CONTENT_TYPE, X_CONTENT_TYPE_OPTIONS}}, response::IntoResponse};
 use crate::middleware::auth::{AuthUser}; 
 use crate::services::fetch::FetchService;
 use crate::utils::{requests::{ValidatedJson, ValidatedPath}, response::{ApiError, WebResponse}};
-use crate::models::fetch::{CreateApiMembers, ReqCreateApi, ReqCreateApiData, ReqCreateApiExecute, ReqCreateApiHeader, UpdateApi, UpdateApiData, UpdateApiExecute, UpdateApiHeader, UpdateApiMembers};
+use crate::models::fetch::{CreateApiMembers, ReqCreateApi, ReqCreateApiCalendar, ReqCreateApiCredential, ReqCreateApiData, ReqCreateApiExecute, ReqCreateApiHeader, UpdateApi, UpdateApiCalendar, UpdateApiCredential, UpdateApiData, UpdateApiExecute, UpdateApiHeader, UpdateApiMembers};
 
 pub async fn get_all(
     uri: Uri,
@@ -59,6 +59,39 @@ pub async fn update_fetch_api(
     Ok(WebResponse::ok(&uri, "Fetch Api Updated!", response))
 }
 
+pub async fn pause_fetch_api(
+    ValidatedPath(id): ValidatedPath<i32>,
+    uri: Uri,
+    AuthUser(user): AuthUser,
+    service: FetchService,
+) -> Result<impl IntoResponse, ApiError> {
+    let response = service.pause_fetch(id, user).await.map_err(|e|e.with_path(&uri))?;
+
+    Ok(WebResponse::ok(&uri, "Fetch Api Paused!", response))
+}
+
+pub async fn resume_fetch_api(
+    ValidatedPath(id): ValidatedPath<i32>,
+    uri: Uri,
+    AuthUser(user): AuthUser,
+    service: FetchService,
+) -> Result<impl IntoResponse, ApiError> {
+    let response = service.resume_fetch(id, user).await.map_err(|e|e.with_path(&uri))?;
+
+    Ok(WebResponse::ok(&uri, "Fetch Api Resumed!", response))
+}
+
+pub async fn run_fetch_api(
+    ValidatedPath(id): ValidatedPath<i32>,
+    uri: Uri,
+    AuthUser(user): AuthUser,
+    service: FetchService,
+) -> Result<impl IntoResponse, ApiError> {
+    let response = service.run_fetch(id, user).await.map_err(|e|e.with_path(&uri))?;
+
+    Ok(WebResponse::ok(&uri, "Fetch Api Run Queued!", response))
+}
+
 pub async fn delete_fetch_api(
     ValidatedPath(id): ValidatedPath<i32>,
     uri: Uri,
@@ -189,6 +222,61 @@ pub async fn delete_fetch_execute(
     Ok(WebResponse::ok(&uri, "Api execute deleted!", response))
 }
 
+pub async fn get_all_calendar(
+    uri: Uri,
+    AuthUser(user): AuthUser,
+    service: FetchService,
+) -> Result<impl IntoResponse, ApiError> {
+    let response = service.get_all_calendar(user).await.map_err(|e|e.with_path(&uri))?;
+
+    Ok(WebResponse::ok(&uri, "List calendars", response))
+}
+
+pub async fn create_fetch_calendar(
+    uri: Uri,
+    AuthUser(user): AuthUser,
+    service: FetchService,
+    ValidatedJson(data): ValidatedJson<ReqCreateApiCalendar>,
+) -> Result<impl IntoResponse, ApiError> {
+    let response = service.create_calendar(user, data).await.map_err(|e|e.with_path(&uri))?;
+
+    Ok(WebResponse::created(&uri, "Calendar imported!", response))
+}
+
+pub async fn get_fetch_calendar(
+    ValidatedPath(id): ValidatedPath<i32>,
+    uri: Uri,
+    AuthUser(user): AuthUser,
+    service: FetchService,
+) -> Result<impl IntoResponse, ApiError> {
+    let response = service.get_calendar(user, id).await.map_err(|e|e.with_path(&uri))?;
+
+    Ok(WebResponse::ok(&uri, "Success!", response))
+}
+
+pub async fn update_fetch_calendar(
+    ValidatedPath(id): ValidatedPath<i32>,
+    uri: Uri,
+    AuthUser(user): AuthUser,
+    service: FetchService,
+    ValidatedJson(data): ValidatedJson<UpdateApiCalendar>
+) -> Result<impl IntoResponse, ApiError> {
+    let response = service.update_calendar(user, id, data).await.map_err(|e|e.with_path(&uri))?;
+
+    Ok(WebResponse::ok(&uri, "Calendar updated!", response))
+}
+
+pub async fn delete_fetch_calendar(
+    ValidatedPath(id): ValidatedPath<i32>,
+    uri: Uri,
+    AuthUser(user): AuthUser,
+    service: FetchService,
+) -> Result<impl IntoResponse, ApiError> {
+    let response = service.delete_calendar(user, id).await.map_err(|e|e.with_path(&uri))?;
+
+    Ok(WebResponse::ok(&uri, "Calendar deleted!", response))
+}
+
 pub async fn get_all_header(
     uri: Uri,
     AuthUser(user): AuthUser,
@@ -244,6 +332,61 @@ pub async fn delete_fetch_header(
     Ok(WebResponse::ok(&uri, "Header deleted!", response))
 }
 
+pub async fn get_all_credential(
+    uri: Uri,
+    AuthUser(user): AuthUser,
+    service: FetchService,
+) -> Result<impl IntoResponse, ApiError> {
+    let response = service.get_all_credential(user).await.map_err(|e|e.with_path(&uri))?;
+
+    Ok(WebResponse::ok(&uri, "List credentials", response))
+}
+
+pub async fn create_fetch_credential(
+    uri: Uri,
+    AuthUser(user): AuthUser,
+    service: FetchService,
+    ValidatedJson(data): ValidatedJson<ReqCreateApiCredential>,
+) -> Result<impl IntoResponse, ApiError> {
+    let response = service.create_credential(user, data).await.map_err(|e|e.with_path(&uri))?;
+
+    Ok(WebResponse::created(&uri, "Credential created!", response))
+}
+
+pub async fn get_fetch_credential(
+    ValidatedPath(id): ValidatedPath<i32>,
+    uri: Uri,
+    AuthUser(user): AuthUser,
+    service: FetchService,
+) -> Result<impl IntoResponse, ApiError> {
+    let response = service.get_credential(user, id).await.map_err(|e|e.with_path(&uri))?;
+
+    Ok(WebResponse::ok(&uri, "Success!", response))
+}
+
+pub async fn update_fetch_credential(
+    ValidatedPath(id): ValidatedPath<i32>,
+    uri: Uri,
+    AuthUser(user): AuthUser,
+    service: FetchService,
+    ValidatedJson(data): ValidatedJson<UpdateApiCredential>
+) -> Result<impl IntoResponse, ApiError> {
+    let response = service.update_credential(user, id, data).await.map_err(|e|e.with_path(&uri))?;
+
+    Ok(WebResponse::ok(&uri, "Credential updated!", response))
+}
+
+pub async fn delete_fetch_credential(
+    ValidatedPath(id): ValidatedPath<i32>,
+    uri: Uri,
+    AuthUser(user): AuthUser,
+    service: FetchService,
+) -> Result<impl IntoResponse, ApiError> {
+    let response = service.delete_credential(user, id).await.map_err(|e|e.with_path(&uri))?;
+
+    Ok(WebResponse::ok(&uri, "Credential deleted!", response))
+}
+
 pub async fn get_all_data(
     ValidatedPath(fetch_id): ValidatedPath<i32>,
     uri: Uri,
@@ -255,6 +398,17 @@ pub async fn get_all_data(
     Ok(WebResponse::ok(&uri, "List fetch data", response))
 }
 
+pub async fn get_all_runs(
+    ValidatedPath(fetch_id): ValidatedPath<i32>,
+    uri: Uri,
+    AuthUser(user): AuthUser,
+    service: FetchService,
+) -> Result<impl IntoResponse, ApiError> {
+    let response = service.get_all_runs(user, fetch_id).await.map_err(|e|e.with_path(&uri))?;
+
+    Ok(WebResponse::ok(&uri, "List fetch runs", response))
+}
+
 pub async fn create_fetch_data(
     ValidatedPath(fetch_id): ValidatedPath<i32>,
     uri: Uri,
@@ -278,6 +432,27 @@ pub async fn get_fetch_data(
     Ok(WebResponse::ok(&uri, "Success!", response))
 }
 
+pub async fn get_fetch_data_raw(
+    ValidatedPath((fetch_id, id)): ValidatedPath<(i32, i32)>,
+    uri: Uri,
+    AuthUser(user): AuthUser,
+    service: FetchService,
+) -> Result<impl IntoResponse, ApiError> {
+    let (content_type, bytes) = service.get_data_raw(user, fetch_id, id).await.map_err(|e|e.with_path(&uri))?;
+    let content_type = content_type
+        .and_then(|c| HeaderValue::from_str(&c).ok())
+        .unwrap_or(HeaderValue::from_static("application/octet-stream"));
+
+    // Target controlled bytes, never rendered on api origin
+    let headers: [(HeaderName, HeaderValue); 3] = [
+        (CONTENT_TYPE, content_type),
+        (X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff")),
+        (CONTENT_DISPOSITION, HeaderValue::from_str(&format!("attachment; filename=\"fetch-data-{}\"", id)).unwrap_or(HeaderValue::from_static("attachment"))),
+    ];
+
+    Ok((headers, bytes))
+}
+
 pub async fn update_fetch_data(
     ValidatedPath((fetch_id, id)): ValidatedPath<(i32, i32)>,
     uri: Uri,
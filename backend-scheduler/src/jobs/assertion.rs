@@ -0,0 +1,135 @@
+use regex::Regex;
+use serde_json::{json, Value};
+use crate::{models::fetch::{AssertOp, Assertions, FetchResult}, utils::json_path::{select, value_to_string}};
+
+/// Schema errors kept per run
+const MAX_SCHEMA_ERRORS: usize = 10;
+
+/// Check response against assertions, report `{passed, results: [{type, target, op, expected, actual, passed, message}]}`
+pub fn evaluate(assertions: &Assertions, result: &FetchResult, latency_ms: u64) -> Value {
+    let mut results: Vec<Value> = Vec::new();
+    let mut check = |kind: &str, target: Value, op: Value, expected: Value, actual: Value, outcome: Result<bool, String>| {
+        let (passed, message) = match outcome {
+            Ok(passed) => (passed, None),
+            Err(msg) => (false, Some(msg)),
+        };
+        results.push(json!({
+            "type": kind, "target": target, "op": op, "expected": expected,
+            "actual": actual, "passed": passed, "message": message,
+        }));
+    };
+
+    if !assertions.status.is_empty() {
+        let outcome = assertions.status.iter()
+            .try_fold(false, |found, rule| Ok::<_, String>(found || rule.matches(result.status_code)?));
+        check("status", Value::Null, json!("in"), json!(assertions.status), json!(result.status_code), outcome);
+    }
+
+    for header in &assertions.headers {
+        let actual = result.headers.as_object()
+            .and_then(|headers| headers.iter().find(|(name, _)| name.eq_ignore_ascii_case(&header.name)))
+            .map(|(_, value)| value.clone());
+        // Header value is text, expected compared as text
+        let expected = match &header.value {
+            Value::Null => Value::Null,
+            other => Value::String(value_to_string(other)),
+        };
+        let outcome = compare(header.op, actual.as_ref(), &expected);
+        check("header", json!(header.name), json!(header.op), header.value.clone(), actual.unwrap_or(Value::Null), outcome);
+    }
+
+    let body: Result<Value, String> = serde_json::from_str(&result.response).map_err(|e| format!("Response body is not JSON: {}", e));
+    for assertion in &assertions.json {
+        let (actual, outcome) = match &body {
+            Ok(body) => {
+                let actual = select(body, &assertion.path);
+                (actual.cloned(), compare(assertion.op, actual, &assertion.value))
+            }
+            Err(e) => (None, Err(e.clone())),
+        };
+        check("json", json!(assertion.path), json!(assertion.op), assertion.value.clone(), actual.unwrap_or(Value::Null), outcome);
+    }
+
+    if let Some(max) = assertions.max_latency_ms {
+        check("latency", Value::Null, json!("lte"), json!(max), json!(latency_ms), Ok(latency_ms <= max));
+    }
+
+    if let Some(schema) = &assertions.schema {
+        let outcome = match (&body, jsonschema::validator_for(schema)) {
+            (Err(e), _) => Err(e.clone()),
+            (_, Err(e)) => Err(format!("Invalid json schema: {}", e)),
+            (Ok(body), Ok(validator)) => {
+                let errors: Vec<String> = validator.iter_errors(body)
+                    .take(MAX_SCHEMA_ERRORS)
+                    .map(|e| format!("{}: {}", e.instance_path(), e))
+                    .collect();
+                if errors.is_empty() { Ok(true) } else { Err(errors.join("; ")) }
+            }
+        };
+        check("schema", Value::Null, json!("valid"), Value::Null, Value::Null, outcome);
+    }
+
+    let passed = results.iter().all(|r| r["passed"] == Value::Bool(true));
+
+    json!({ "passed": passed, "results": results })
+}
+
+/// Failed assertions message, None when passed
+pub fn failure_message(report: &Value) -> Option<String> {
+    if report["passed"] == Value::Bool(true) {
+        return None;
+    }
+    let failed: Vec<String> = report["results"].as_array()
+        .into_iter()
+        .flatten()
+        .filter(|r| r["passed"] != Value::Bool(true))
+        .map(|r| match &r["target"] {
+            Value::Null => value_to_string(&r["type"]),
+            target => format!("{} {}", value_to_string(&r["type"]), value_to_string(target)),
+        })
+        .collect();
+
+    Some(format!("Assertion failed: {}", failed.join(", ")))
+}
+
+fn compare(op: AssertOp, actual: Option<&Value>, expected: &Value) -> Result<bool, String> {
+    let Some(actual) = actual else {
+        return Ok(op == AssertOp::NotExists);
+    };
+    Ok(match op {
+        AssertOp::Exists => true,
+        AssertOp::NotExists => false,
+        AssertOp::Eq => equals(actual, expected),
+        AssertOp::Ne => !equals(actual, expected),
+        AssertOp::Gt | AssertOp::Gte | AssertOp::Lt | AssertOp::Lte => {
+            let (a, b) = (number(actual).ok_or("Actual value is not a number")?, number(expected).ok_or("Expected value is not a number")?);
+            match op {
+                AssertOp::Gt => a > b,
+                AssertOp::Gte => a >= b,
+                AssertOp::Lt => a < b,
+                _ => a <= b,
+            }
+        }
+        AssertOp::Contains => match actual {
+            Value::Array(items) => items.iter().any(|item| equals(item, expected)),
+            other => value_to_string(other).contains(&value_to_string(expected)),
+        },
+        AssertOp::Regex => {
+            let pattern = expected.as_str().ok_or("Regex value must be a string")?;
+            Regex::new(pattern).map_err(|e| e.to_string())?.is_match(&value_to_string(actual))
+        }
+    })
+}
+
+/// Numbers equal by value (1 == 1.0)
+fn equals(actual: &Value, expected: &Value) -> bool {
+    match (actual.as_f64(), expected.as_f64()) {
+        (Some(a), Some(b)) => a == b,
+        _ => actual == expected,
+    }
+}
+
+/// Number or numeric string
+fn number(value: &Value) -> Option<f64> {
+    value.as_f64().or_else(|| value.as_str()?.trim().parse().ok())
+}
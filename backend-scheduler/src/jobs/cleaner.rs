@@ -1,7 +1,7 @@
 use sqlx::PgPool;
 use std::time::Duration;
 
-/// Clean apalis.jobs
+/// Clean apalis.jobs and run history
 pub async fn start_job_cleaner(pool: PgPool) {
     let mut interval = tokio::time::interval(Duration::from_hours(12));
 
@@ -22,5 +22,17 @@ pub async fn start_job_cleaner(pool: PgPool) {
             Ok(res) => tracing::info!("Deleted {} old jobs.", res.rows_affected()),
             Err(e) => tracing::error!("Failed to clean jobs: {:?}", e),
         }
+
+        let result = sqlx::query(r#"
+            DELETE FROM fetch_api_runs
+            WHERE started_at < NOW() - INTERVAL '30 days'
+        "#)
+        .execute(&pool)
+        .await;
+
+        match result {
+            Ok(res) => tracing::info!("Deleted {} old run history.", res.rows_affected()),
+            Err(e) => tracing::error!("Failed to clean run history: {:?}", e),
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,164 @@
+use hickory_resolver::{Resolver, config::{NameServerConfigGroup, ResolverConfig, ResolverOpts}, name_server::TokioConnectionProvider, proto::rr::{RData, Record, RecordType}};
+use serde_json::{Value, json};
+use std::{collections::BTreeSet, net::{IpAddr, SocketAddr}, time::{Duration, Instant}};
+use crate::models::fetch::{DnsExpectedMatch, DnsOptions, DnsRecordType, FetchResult};
+use tracing::debug;
+
+
+#[derive(Clone)]
+pub struct DnsJobs {
+    timeout_duration: Duration,
+}
+
+impl DnsJobs {
+    pub fn new(timeout: u64) -> Self {
+        Self {
+            timeout_duration: Duration::from_secs(timeout),
+        }
+    }
+
+    pub async fn request_response(&self, target_url: &str, options: &Option<Value>) -> Result<FetchResult, String> {
+        let options = DnsOptions::parse(options)?;
+        let domain = target_url
+            .split_once("://")
+            .map(|(_, rest)| rest)
+            .unwrap_or(target_url)
+            .trim_end_matches('/');
+        let record_type = match options.record_type {
+            DnsRecordType::A => RecordType::A,
+            DnsRecordType::Aaaa => RecordType::AAAA,
+            DnsRecordType::Cname => RecordType::CNAME,
+            DnsRecordType::Mx => RecordType::MX,
+            DnsRecordType::Txt => RecordType::TXT,
+            DnsRecordType::Srv => RecordType::SRV,
+        };
+
+        let mut resolver_opts = ResolverOpts::default();
+        resolver_opts.timeout = options.timeout.map(Duration::from_secs).unwrap_or(self.timeout_duration);
+        resolver_opts.attempts = 2;
+
+        // Fresh resolver per run, cached answer would hide the real TTL
+        let resolver = match &options.nameserver {
+            Some(nameserver) => {
+                let address = nameserver_address(nameserver)?;
+                let group = NameServerConfigGroup::from_ips_clear(&[address.ip()], address.port(), true);
+                Resolver::builder_with_config(ResolverConfig::from_parts(None, vec![], group), TokioConnectionProvider::default())
+            }
+            None => Resolver::builder_tokio()
+                .map_err(|e| format!("Failed read system resolver config: {}", e))?,
+        }
+        .with_options(resolver_opts)
+        .build();
+
+        let started = Instant::now();
+        let lookup = resolver.lookup(domain, record_type).await;
+        let duration_ms = started.elapsed().as_millis() as u64;
+
+        let (rcode, answers) = match lookup {
+            Ok(lookup) => ("NOERROR", lookup.records().iter().filter(|r| r.record_type() == record_type).map(record_to_json).collect::<Vec<Value>>()),
+            Err(e) if e.is_nx_domain() => ("NXDOMAIN", Vec::new()),
+            Err(e) if e.is_no_records_found() => ("NOERROR", Vec::new()),
+            Err(e) => return Err(format!("Failed lookup {} {}: {}", record_type, domain, e)),
+        };
+        debug!("[DNS] {} {} -> {} answer", record_type, domain, answers.len());
+
+        // Compare with expected answer set
+        let is_text = options.record_type == DnsRecordType::Txt;
+        let actual: BTreeSet<String> = answers.iter()
+            .filter_map(|a| a["value"].as_str())
+            .map(|v| normalize_value(v, is_text))
+            .collect();
+        let mut error = None;
+        let matched = match &options.expected {
+            Some(expected) => {
+                let expected: BTreeSet<String> = expected.iter().map(|v| normalize_value(v, is_text)).collect();
+                let is_match = match options.expected_match {
+                    DnsExpectedMatch::Exact => actual == expected,
+                    DnsExpectedMatch::Contains => expected.is_subset(&actual),
+                };
+                if !is_match {
+                    error = Some(format!("DNS answer mismatch: expected {:?}, got {:?}", expected, actual));
+                }
+                Some(is_match)
+            }
+            None => None,
+        };
+        if rcode == "NXDOMAIN" && error.is_none() {
+            error = Some(format!("Domain not found: {}", domain));
+        }
+
+        let response = json!({
+            "domain": domain,
+            "record_type": record_type.to_string(),
+            "nameserver": options.nameserver.clone().unwrap_or("system".to_string()),
+            "rcode": rcode,
+            "duration_ms": duration_ms,
+            "answers": answers,
+            "expected": options.expected,
+            "matched": matched,
+        });
+
+        Ok(FetchResult {
+            status_code: 0,
+            headers: json!({ "rcode": rcode }),
+            response: response.to_string(),
+            error,
+            meta: None,
+            content_type: None,
+            response_raw: None,
+        })
+    }
+}
+
+fn nameserver_address(nameserver: &str) -> Result<SocketAddr, String> {
+    if let Ok(address) = nameserver.parse::<SocketAddr>() {
+        return Ok(address);
+    }
+
+    nameserver.trim_matches(|c| c == '[' || c == ']')
+        .parse::<IpAddr>()
+        .map(|ip| SocketAddr::new(ip, 53))
+        .map_err(|_| format!("Invalid nameserver, expected ip or ip:port: {}", nameserver))
+}
+
+fn normalize_value(value: &str, is_text: bool) -> String {
+    if is_text {
+        return value.to_string();
+    }
+
+    value.trim().trim_end_matches('.').to_lowercase()
+}
+
+fn record_to_json(record: &Record) -> Value {
+    let mut answer = json!({
+        "name": record.name().to_string(),
+        "type": record.record_type().to_string(),
+        "ttl": record.ttl(),
+    });
+
+    let (value, detail) = match record.data() {
+        RData::A(ip) => (ip.to_string(), Value::Null),
+        RData::AAAA(ip) => (ip.to_string(), Value::Null),
+        RData::CNAME(name) => (name.to_string(), Value::Null),
+        RData::MX(mx) => (
+            format!("{} {}", mx.preference(), mx.exchange()),
+            json!({ "preference": mx.preference(), "exchange": mx.exchange().to_string() }),
+        ),
+        RData::TXT(txt) => (
+            txt.iter().map(|t| String::from_utf8_lossy(t).to_string()).collect::<String>(),
+            Value::Null,
+        ),
+        RData::SRV(srv) => (
+            format!("{} {} {} {}", srv.priority(), srv.weight(), srv.port(), srv.target()),
+            json!({ "priority": srv.priority(), "weight": srv.weight(), "port": srv.port(), "target": srv.target().to_string() }),
+        ),
+        other => (other.to_string(), Value::Null),
+    };
+
+    answer["value"] = Value::String(value);
+    if !detail.is_null() {
+        answer["detail"] = detail;
+    }
+
+    answer
+}
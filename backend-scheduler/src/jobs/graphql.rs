@@ -0,0 +1,82 @@
+use reqwest::{Method, header::{ACCEPT, CONTENT_TYPE, HeaderValue}};
+use serde_json::{Map, Value, json};
+use std::time::Instant;
+use crate::{jobs::{http::{HttpClients, read_body, send_with_redirects}, timing}, models::fetch::{ApiCredential, FetchResult, GraphqlPayload, HttpOptions}, utils::reqwest::{headermap_to_json, json_to_headermap}};
+
+pub async fn request_response(http_clients: &HttpClients, target_url: &str, payload: &Option<String>, options: &Option<Value>, headers: Option<Value>, credential: &Option<ApiCredential>) -> Result<FetchResult, String> {
+    let graphql = GraphqlPayload::parse(payload)?;
+    let options = HttpOptions::parse(options)?;
+    let http_client = http_clients.get(&options, credential)?;
+    let mut headers_map = json_to_headermap(headers).await;
+    headers_map.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    if !headers_map.contains_key(ACCEPT) {
+        headers_map.insert(ACCEPT, HeaderValue::from_static("application/graphql-response+json, application/json"));
+    }
+
+    let mut body = Map::new();
+    body.insert("query".to_string(), Value::String(graphql.query));
+    if let Some(name) = graphql.operation_name {
+        body.insert("operationName".to_string(), Value::String(name));
+    }
+    if let Some(vars) = graphql.variables {
+        body.insert("variables".to_string(), vars);
+    }
+
+    let body = Value::Object(body).to_string();
+    let started = Instant::now();
+    let (sent, phases) = timing::measure(send_with_redirects(&http_client, Method::POST, target_url, headers_map, &[], options.max_redirects, |request_builder| {
+        Ok(request_builder.body(body.clone()))
+    })).await;
+    let (response, redirects) = sent?;
+    let ttfb = started.elapsed();
+    let final_url = response.url().to_string();
+
+    let status_obj = response.status();
+    let status_code = status_obj.as_u16() as i16;
+
+    let response_headers_json = headermap_to_json(response.headers());
+
+    let body = read_body(response, http_clients.body_cap(&options)).await?;
+    let timing = phases.to_json(ttfb, started.elapsed(), body.size);
+    let meta = Some(json!({ "url": final_url, "redirects": redirects, "body": body.meta, "timing": timing }));
+
+    // Non JSON body (proxy error page etc), keep as is
+    let Ok(Value::Object(mut res_json)) = serde_json::from_str::<Value>(&body.text) else {
+        return Ok(FetchResult {
+            status_code,
+            headers: response_headers_json,
+            response: body.text,
+            error: Some(format!("Invalid GraphQL response [{}]", status_code)),
+            meta,
+            content_type: body.content_type,
+            response_raw: body.raw,
+        });
+    };
+
+    let data = res_json.remove("data").unwrap_or(Value::Null);
+    let errors = res_json.remove("errors").unwrap_or(Value::Null);
+    let extensions = res_json.remove("extensions").unwrap_or(Value::Null);
+
+    let error = match &errors {
+        Value::Array(list) if !list.is_empty() => {
+            let messages: Vec<String> = list.iter()
+                .map(|e| e.get("message").and_then(Value::as_str).unwrap_or("unknown error").to_string())
+                .collect();
+            Some(format!("GraphQL errors: {}", messages.join("; ")))
+        }
+        _ if !status_obj.is_success() => Some(format!("GraphQL request failed [{}]", status_code)),
+        _ => None,
+    };
+
+    let result = FetchResult {
+        status_code,
+        headers: response_headers_json,
+        response: json!({ "data": data, "errors": errors, "extensions": extensions }).to_string(),
+        error,
+        meta,
+        content_type: Some("application/json".to_string()),
+        response_raw: None,
+    };
+
+    Ok(result)
+}
@@ -0,0 +1,257 @@
+use encoding_rs::{Encoding, UTF_8};
+use reqwest::{Client, Method, Proxy, RequestBuilder, Response, Url, header::{AUTHORIZATION, CONTENT_TYPE, COOKIE, HeaderMap, LOCATION, PROXY_AUTHORIZATION}, redirect::Policy};
+use serde_json::{Value, json};
+use std::{collections::HashMap, sync::{Arc, RwLock}, time::Duration};
+use crate::{jobs::timing::{self, TimedConnectLayer, TimedResolver}, models::fetch::{ApiCredential, HttpOptions, HttpVersion}, utils::tls::{accept_invalid_certs, client_config, client_config_from_pem}};
+use tracing::debug;
+
+const DEFAULT_MAX_REDIRECTS: usize = 10;
+const MAX_CACHED_CLIENTS: usize = 64;
+/// Default response body cap (10 MiB), also default of MAX_BODY_BYTES
+pub const DEFAULT_MAX_BODY_BYTES: usize = 10 * 1024 * 1024;
+
+/// Client settings + credential (id, updated_at) as cache key
+type ClientKey = (HttpOptions, Option<(i32, i64)>);
+
+/// Http clients cached per distinct client settings
+#[derive(Clone)]
+pub struct HttpClients {
+    timeout_duration: Duration,
+    max_body_bytes: usize,
+    clients: Arc<RwLock<HashMap<ClientKey, Client>>>,
+}
+
+impl HttpClients {
+    pub fn new(timeout: u64, max_body_bytes: usize) -> Self {
+        Self {
+            timeout_duration: Duration::from_secs(timeout),
+            max_body_bytes,
+            clients: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Body cap of options, never above server MAX_BODY_BYTES
+    pub fn body_cap(&self, options: &HttpOptions) -> usize {
+        options.max_body_bytes.unwrap_or(DEFAULT_MAX_BODY_BYTES).min(self.max_body_bytes)
+    }
+
+    /// Client for options & credential, redirect handled by `send_with_redirects`
+    pub fn get(&self, options: &HttpOptions, credential: &Option<ApiCredential>) -> Result<Client, String> {
+        // Redirect limit & body cap are not client settings
+        let options = HttpOptions { max_redirects: None, max_body_bytes: None, ..options.clone() };
+        let key: ClientKey = (options, credential.as_ref().map(|c| (c.id, c.updated_at.timestamp_micros())));
+        if let Ok(clients) = self.clients.read()
+            && let Some(client) = clients.get(&key) {
+            return Ok(client.clone());
+        }
+        let options = &key.0;
+
+        let mut builder = Client::builder()
+            .user_agent(options.user_agent.clone().unwrap_or("Teknohole/1.0".to_string()))
+            .timeout(options.timeout.map(Duration::from_secs).unwrap_or(self.timeout_duration))
+            .pool_idle_timeout(Duration::from_secs(90))
+            .pool_max_idle_per_host(10)
+            .redirect(Policy::none())
+            // Phase timing hooks, see jobs::timing
+            .dns_resolver(Arc::new(TimedResolver))
+            .connector_layer(TimedConnectLayer);
+        if let Some(proxy) = &options.proxy {
+            builder = builder.proxy(Proxy::all(proxy).map_err(|e| format!("Invalid proxy: {}", e))?);
+        }
+        builder = match options.http_version {
+            HttpVersion::Auto => builder,
+            HttpVersion::Http1 => builder.http1_only(),
+            HttpVersion::Http2 => builder.http2_prior_knowledge(),
+        };
+        // mTLS / private CA (certificate always verified), else webpki roots
+        let mut tls = match credential {
+            Some(credential) => client_config(credential)?,
+            None => {
+                let mut tls = client_config_from_pem(&None, &None, &None)?;
+                if options.accept_invalid_certs {
+                    accept_invalid_certs(&mut tls);
+                }
+                tls
+            }
+        };
+        tls.alpn_protocols = match options.http_version {
+            HttpVersion::Auto => vec![b"h2".to_vec(), b"http/1.1".to_vec()],
+            HttpVersion::Http1 => vec![b"http/1.1".to_vec()],
+            HttpVersion::Http2 => vec![b"h2".to_vec()],
+        };
+        timing::mark_tcp_connected(&mut tls);
+        builder = builder.use_preconfigured_tls(tls);
+        let client = builder.build().map_err(|e| format!("Failed build http client: {}", e))?;
+
+        if let Ok(mut clients) = self.clients.write() {
+            if clients.len() >= MAX_CACHED_CLIENTS {
+                clients.clear();
+            }
+            debug!("[HTTP] New client cached ({} total)", clients.len() + 1);
+            clients.insert(key, client.clone());
+        }
+
+        Ok(client)
+    }
+}
+
+/// Send request and follow redirect manually, return final response and redirect chain.
+/// Query only on first request (location carry its own), body dropped when method changed to GET.
+pub async fn send_with_redirects<F>(client: &Client, method: Method, url: &str, headers: HeaderMap, query: &[(String, String)], max_redirects: Option<usize>, body: F) -> Result<(Response, Vec<Value>), String>
+where
+    F: Fn(RequestBuilder) -> Result<RequestBuilder, String>,
+{
+    let max_redirects = max_redirects.unwrap_or(DEFAULT_MAX_REDIRECTS);
+    let mut method = method;
+    let mut url = Url::parse(url).map_err(|e| format!("Invalid URL: {}", e))?;
+    let mut headers = headers;
+    let mut with_body = true;
+    let mut first = true;
+    let mut chain: Vec<Value> = Vec::new();
+
+    loop {
+        let mut request_builder = client
+            .request(method.clone(), url.clone())
+            .headers(headers.clone());
+        if first && !query.is_empty() {
+            request_builder = request_builder.query(query);
+        }
+        if with_body {
+            request_builder = body(request_builder)?;
+        }
+        first = false;
+
+        // Phases of the last hop only
+        timing::reset();
+        let response = request_builder.send()
+            .await.map_err(|e| format!("Failed send message: {}", e))?;
+
+        let status = response.status();
+        let location = response.headers().get(LOCATION).and_then(|l| l.to_str().ok());
+        let (true, Some(location)) = (status.is_redirection(), location) else {
+            return Ok((response, chain));
+        };
+        if chain.len() >= max_redirects {
+            if max_redirects > 0 {
+                return Err(format!("Too many redirects (max {})", max_redirects));
+            }
+            return Ok((response, chain));
+        }
+
+        let next = url.join(location).map_err(|e| format!("Invalid redirect location {}: {}", location, e))?;
+        chain.push(json!({ "url": url.as_str(), "status": status.as_u16(), "location": next.as_str() }));
+        debug!("[HTTP] Redirect {} -> {}", status.as_u16(), next);
+
+        // 303 (and 301/302 after POST) continue as GET without body
+        if status.as_u16() == 303 && method != Method::HEAD
+            || matches!(status.as_u16(), 301 | 302) && method == Method::POST {
+            method = Method::GET;
+            with_body = false;
+        }
+        // Credential header not sent to other host
+        if next.host_str() != url.host_str() || next.port_or_known_default() != url.port_or_known_default() {
+            headers.remove(AUTHORIZATION);
+            headers.remove(COOKIE);
+            headers.remove(PROXY_AUTHORIZATION);
+        }
+        url = next;
+    }
+}
+
+/// Response body read with size cap
+pub struct CapturedBody {
+    /// Decoded text, empty for binary body
+    pub text: String,
+    /// Original bytes, only kept when body is binary or not cleanly decoded
+    pub raw: Option<Vec<u8>>,
+    pub content_type: Option<String>,
+    /// `{size, truncated, charset, encoding}` for fetch meta
+    pub meta: Value,
+    /// Body bytes read (after content decoding, up to cap)
+    pub size: usize,
+}
+
+/// Stream body up to `max_bytes` (rest is dropped and flagged), decode text by charset
+pub async fn read_body(response: Response, max_bytes: usize) -> Result<CapturedBody, String> {
+    let content_type = response.headers()
+        .get(CONTENT_TYPE)
+        .and_then(|c| c.to_str().ok())
+        .map(str::to_string);
+
+    let mut response = response;
+    let mut bytes: Vec<u8> = Vec::new();
+    let mut truncated = false;
+    while let Some(chunk) = response.chunk()
+        .await.map_err(|e| format!("Failed response message: {}", e))? {
+        let room = max_bytes - bytes.len();
+        if chunk.len() > room {
+            bytes.extend_from_slice(&chunk[..room]);
+            truncated = true;
+            break;
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    if truncated {
+        debug!("[HTTP] Response body truncated at {} bytes", max_bytes);
+    }
+
+    let read = bytes.len();
+    let (text, raw, charset, encoding) = match decode_text(content_type.as_deref(), &bytes) {
+        Some((text, encoding, clean)) => {
+            // Non utf-8 / lossy text keep original bytes for raw download
+            let keep_raw = !clean || encoding != UTF_8;
+            (text, keep_raw.then_some(bytes), Some(encoding.name()), "text")
+        }
+        None => (String::new(), Some(bytes), None, "binary"),
+    };
+    let size = raw.as_ref().map(Vec::len).unwrap_or(text.len());
+
+    Ok(CapturedBody {
+        text,
+        raw,
+        content_type,
+        meta: json!({ "size": size, "truncated": truncated, "charset": charset, "encoding": encoding }),
+        size: read,
+    })
+}
+
+/// Text body decoded by charset param, BOM, then utf-8. None for binary content.
+/// Return (text, encoding, decoded without replacement)
+fn decode_text(content_type: Option<&str>, bytes: &[u8]) -> Option<(String, &'static Encoding, bool)> {
+    let mime = content_type
+        .and_then(|c| c.split(';').next())
+        .map(|m| m.trim().to_ascii_lowercase());
+    let charset = content_type.and_then(|c| {
+        c.split(';')
+            .skip(1)
+            .filter_map(|p| p.split_once('='))
+            .find(|(k, _)| k.trim().eq_ignore_ascii_case("charset"))
+            .and_then(|(_, v)| Encoding::for_label(v.trim().trim_matches('"').as_bytes()))
+    });
+
+    match mime.as_deref() {
+        Some(mime) if is_text_mime(mime) => {}
+        // Unknown type, text only when valid utf-8
+        None | Some("") => {
+            std::str::from_utf8(bytes).ok()?;
+        }
+        Some(_) if charset.is_some() => {}
+        Some(_) => return None,
+    }
+
+    let (encoding, bom_len) = Encoding::for_bom(bytes)
+        .unwrap_or((charset.unwrap_or(UTF_8), 0));
+    let (text, had_errors) = encoding.decode_without_bom_handling(&bytes[bom_len..]);
+
+    Some((text.into_owned(), encoding, !had_errors))
+}
+
+fn is_text_mime(mime: &str) -> bool {
+    mime.starts_with("text/")
+        || mime.ends_with("+json")
+        || mime.ends_with("+xml")
+        || matches!(mime,
+            "application/json" | "application/xml" | "application/javascript" | "application/ecmascript"
+            | "application/x-www-form-urlencoded" | "application/graphql" | "application/x-ndjson"
+            | "application/yaml" | "application/x-yaml" | "application/toml")
+}
@@ -1,4 +1,16 @@
 pub mod workers;
 pub mod cleaner;
+pub mod http;
 pub mod rest;
-pub mod websocket;
\ No newline at end of file
+pub mod websocket;
+pub mod socketio;
+pub mod mqtt;
+pub mod graphql;
+pub mod sse;
+pub mod socket;
+pub mod tls;
+pub mod dns;
+pub mod sql;
+pub mod retry;
+pub mod assertion;
+pub mod timing;
\ No newline at end of file
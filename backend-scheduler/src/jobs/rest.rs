@@ -1,9 +1,16 @@
-use reqwest::{Client, Method};
-use serde_json::{Map, Value};
-use crate::{models::fetch::{ApiMethod, FetchResult}, utils::reqwest::json_to_headermap};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use reqwest::{Method, multipart::{Form, Part}};
+use serde_json::{Value, json};
+use std::time::Instant;
+use crate::{jobs::{http::{HttpClients, read_body, send_with_redirects}, timing}, models::fetch::{ApiCredential, ApiMethod, BodyMode, FetchResult, HttpOptions, MultipartPart, RequestBody, query_pairs}, utils::reqwest::{headermap_to_json, json_to_headermap}};
 
-pub async fn request_response(http_client: Client, target_url: &str, method: &Option<ApiMethod>, payload: &Option<String>, headers: Option<Value>) -> Result<FetchResult, String> {
+#[allow(clippy::too_many_arguments)]
+pub async fn request_response(http_clients: &HttpClients, target_url: &str, method: &Option<ApiMethod>, query: &Option<Value>, body_mode: &BodyMode, payload: &Option<String>, options: &Option<Value>, headers: Option<Value>, credential: &Option<ApiCredential>) -> Result<FetchResult, String> {
+    let options = HttpOptions::parse(options)?;
+    let http_client = http_clients.get(&options, credential)?;
     let headers_map = json_to_headermap(headers).await; 
+    let query = query_pairs(query)?;
+    let body = RequestBody::parse(body_mode, payload)?;
 
     let req_method = match method {
         Some(ApiMethod::Get) => Method::GET,
@@ -11,42 +18,68 @@ pub async fn request_response(http_client: Client, target_url: &str, method: &Op
         Some(ApiMethod::Put) => Method::PUT,
         Some(ApiMethod::Delete) => Method::DELETE,
         Some(ApiMethod::Patch) => Method::PATCH,
+        Some(ApiMethod::Head) => Method::HEAD,
+        Some(ApiMethod::Options) => Method::OPTIONS,
         None => Method::GET, 
         // _ => Method::GET,
     };
 
-    let mut request_builder = http_client
-        .request(req_method, target_url)
-        .headers(headers_map);
-
-    if let Some(payload) = payload {
-        if !payload.is_empty() {
-             request_builder = request_builder.body(payload.clone());
-        }
-    }
-
-    let response = request_builder.send()
-        .await.map_err(|e| format!("Failed send message: {}", e))?;
+    // Body & Content-Type by body mode
+    let started = Instant::now();
+    let (sent, phases) = timing::measure(send_with_redirects(&http_client, req_method, target_url, headers_map, &query, options.max_redirects, |request_builder| {
+        Ok(match &body {
+            RequestBody::Empty => request_builder,
+            RequestBody::Raw(raw) => request_builder.body(raw.clone()),
+            RequestBody::Json(json) => request_builder.json(json),
+            RequestBody::Form(pairs) => request_builder.form(pairs),
+            RequestBody::Multipart(parts) => request_builder.multipart(multipart_form(parts)?),
+        })
+    })).await;
+    let (response, redirects) = sent?;
+    let ttfb = started.elapsed();
+    let final_url = response.url().to_string();
 
     let status_obj = response.status();
     let status_code = status_obj.as_u16() as i16;
 
-    let mut response_headers_map = Map::new();
-    for (k, v) in response.headers() {
-        let key_str = k.to_string();
-        let val_str = v.to_str().unwrap_or("").to_string();
-        response_headers_map.insert(key_str, Value::String(val_str));
-    }
-    let response_headers_json = Value::Object(response_headers_map);
+    let response_headers_json = headermap_to_json(response.headers());
 
-    let res_text = response.text()
-        .await.map_err(|e| format!("Failed response message: {}", e))?;
+    let body = read_body(response, http_clients.body_cap(&options)).await?;
+    let timing = phases.to_json(ttfb, started.elapsed(), body.size);
 
     let result = FetchResult { 
         status_code: status_code,
         headers: response_headers_json,
-        response: res_text
+        response: body.text,
+        error: None,
+        meta: Some(json!({ "url": final_url, "redirects": redirects, "body": body.meta, "timing": timing })),
+        content_type: body.content_type,
+        response_raw: body.raw,
     };
 
     Ok(result)
-}
\ No newline at end of file
+}
+fn multipart_form(parts: &[MultipartPart]) -> Result<Form, String> {
+    let mut form = Form::new();
+    for part in parts {
+        let mut item = match (&part.value, &part.content_base64) {
+            (Some(value), _) => Part::text(value.clone()),
+            (None, Some(content)) => {
+                let bytes = STANDARD.decode(content.trim())
+                    .map_err(|e| format!("Invalid base64 content for part '{}': {}", part.name, e))?;
+                Part::bytes(bytes)
+            }
+            (None, None) => Part::text(String::new()),
+        };
+        if let Some(filename) = &part.filename {
+            item = item.file_name(filename.clone());
+        }
+        if let Some(content_type) = &part.content_type {
+            item = item.mime_str(content_type)
+                .map_err(|e| format!("Invalid content type for part '{}': {}", part.name, e))?;
+        }
+        form = form.part(part.name.clone(), item);
+    }
+
+    Ok(form)
+}
@@ -0,0 +1,59 @@
+use chrono::{DateTime, Duration, Utc};
+use serde_json::Value;
+use crate::models::fetch::{Backoff, FetchResult, RetryOn, RetryPolicy};
+
+/// Server cap of retry delay (1 day), also applied to Retry-After
+pub const MAX_RETRY_DELAY_SECONDS: u64 = 24 * 60 * 60;
+
+/// Failed outcome of a done request, None when the run succeeded
+pub fn failure(result: &FetchResult) -> Option<(RetryOn, String)> {
+    if let Some(msg) = &result.error {
+        return Some((RetryOn::Network, msg.clone()));
+    }
+    match result.status_code {
+        429 => Some((RetryOn::RateLimit, "Rate limited [429]".to_string())),
+        500..=599 => Some((RetryOn::Server, format!("Server error [{}]", result.status_code))),
+        _ => None,
+    }
+}
+
+/// Retry-After response header, delay seconds or HTTP date, clamped to MAX_RETRY_DELAY_SECONDS
+pub fn retry_after(headers: &Value, now: DateTime<Utc>) -> Option<Duration> {
+    let max = Duration::seconds(MAX_RETRY_DELAY_SECONDS as i64);
+    let value = headers.as_object()?
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("retry-after"))?
+        .1
+        .as_str()?
+        .trim();
+
+    match value.parse::<u64>() {
+        Ok(seconds) => Some(Duration::seconds(seconds.min(MAX_RETRY_DELAY_SECONDS) as i64)),
+        // Overflowing delay seconds
+        Err(_) if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) => Some(max),
+        Err(_) => DateTime::parse_from_rfc2822(value)
+            .ok()
+            .map(|at| (at.with_timezone(&Utc) - now).clamp(Duration::zero(), max)),
+    }
+}
+
+/// Delay before retry of failed `attempt` (1 = first run), `jitter` random in 0..1.
+/// Retry-After wins over shorter backoff, both capped by max_delay_seconds and MAX_RETRY_DELAY_SECONDS.
+pub fn retry_delay(policy: &RetryPolicy, attempt: u32, retry_after: Option<Duration>, jitter: f64) -> Duration {
+    let cap = policy.max_delay_seconds.map_or(MAX_RETRY_DELAY_SECONDS, |cap| cap.min(MAX_RETRY_DELAY_SECONDS));
+    let base = policy.delay_seconds;
+    let seconds = match policy.backoff {
+        Backoff::Fixed => base,
+        Backoff::Linear => base.saturating_mul(attempt as u64),
+        Backoff::Exponential => base.saturating_mul(2u64.saturating_pow(attempt.saturating_sub(1))),
+    };
+    // Equal jitter: half fixed, half random
+    let millis = (seconds.min(cap) * 1000) as f64;
+    let millis = if policy.jitter { millis / 2.0 + millis / 2.0 * jitter.clamp(0.0, 1.0) } else { millis };
+    let delay = Duration::milliseconds(millis as i64);
+
+    match retry_after {
+        Some(wait) if policy.respect_retry_after => delay.max(wait).min(Duration::seconds(cap as i64)),
+        _ => delay,
+    }
+}
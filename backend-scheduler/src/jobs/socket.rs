@@ -0,0 +1,181 @@
+use base64::{Engine, engine::general_purpose::STANDARD};
+use serde_json::{Value, json};
+use std::time::{Duration, Instant};
+use tokio::{io::{AsyncReadExt, AsyncWriteExt}, net::{TcpStream, UdpSocket, lookup_host}, time::{self, timeout_at}};
+use crate::models::fetch::{FetchResult, PayloadEncoding, SocketOptions};
+use tracing::debug;
+
+
+#[derive(Clone)]
+pub struct SocketJobs {
+    timeout_duration: Duration,
+}
+
+impl SocketJobs {
+    pub fn new(timeout: u64) -> Self {
+        Self {
+            timeout_duration: Duration::from_secs(timeout),
+        }
+    }
+
+    pub async fn tcp_request_response(&self, target_url: &str, payload: &Option<String>, options: &Option<Value>) -> Result<FetchResult, String> {
+        let options = SocketOptions::parse(options)?;
+        let data = decode_payload(payload, &options.encoding)?;
+        let timeout_duration = options.timeout.map(Duration::from_secs).unwrap_or(self.timeout_duration);
+        let address = socket_address(target_url, "tcp")?;
+
+        // Connect, send and read share one deadline
+        let started = Instant::now();
+        let deadline = time::Instant::now() + timeout_duration;
+        let mut stream = timeout_at(deadline, TcpStream::connect(&address))
+            .await
+            .map_err(|_| format!("Failed connect to {}: timeout", address))?
+            .map_err(|e| format!("Failed connect to {}: {}", address, e))?;
+        let connect_ms = started.elapsed().as_millis() as u64;
+        let peer = stream.peer_addr().map(|a| a.to_string()).unwrap_or_default();
+
+        // Send Payload
+        if !data.is_empty() {
+            debug!("[TCP] Sending payload to {}...", address);
+            timeout_at(deadline, stream.write_all(&data))
+                .await
+                .map_err(|_| "Failed send message: timeout".to_string())?
+                .map_err(|e| format!("Failed send message: {}", e))?;
+        }
+
+        // Collect reply (Logic Timeout)
+        let mut reply: Vec<u8> = Vec::new();
+        let mut buffer = [0u8; 4096];
+        let stop_reason = loop {
+            if let Some(limit) = options.read_bytes && reply.len() >= limit {
+                reply.truncate(limit);
+                break "bytes";
+            }
+            if let Some(delimiter) = &options.read_until
+                && !delimiter.is_empty()
+                && reply.windows(delimiter.len()).any(|w| w == delimiter.as_bytes()) {
+                break "delimiter";
+            }
+
+            match timeout_at(deadline, stream.read(&mut buffer)).await {
+                Ok(Ok(0)) => break "eof",
+                Ok(Ok(n)) => reply.extend_from_slice(&buffer[..n]),
+                Ok(Err(e)) => return Err(format!("[TCP] Error: {}", e)),
+                Err(_) => break "timeout",
+            }
+        };
+        let total_ms = started.elapsed().as_millis() as u64;
+        let _ = stream.shutdown().await;
+
+        debug!("[TCP] Reply {} bytes, stop by {}", reply.len(), stop_reason);
+        Ok(probe_result("tcp", &address, &peer, Some(connect_ms), total_ms, data.len(), &reply, stop_reason))
+    }
+
+    pub async fn udp_request_response(&self, target_url: &str, payload: &Option<String>, options: &Option<Value>) -> Result<FetchResult, String> {
+        let options = SocketOptions::parse(options)?;
+        let data = decode_payload(payload, &options.encoding)?;
+        let timeout_duration = options.timeout.map(Duration::from_secs).unwrap_or(self.timeout_duration);
+        let address = socket_address(target_url, "udp")?;
+
+        let started = Instant::now();
+        let deadline = time::Instant::now() + timeout_duration;
+        let target = timeout_at(deadline, lookup_host(&address))
+            .await
+            .map_err(|_| format!("Failed resolve {}: timeout", address))?
+            .map_err(|e| format!("Failed resolve {}: {}", address, e))?
+            .next()
+            .ok_or(format!("Failed resolve {}: no address", address))?;
+        let bind = if target.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+        let socket = UdpSocket::bind(bind)
+            .await
+            .map_err(|e| format!("Failed bind udp socket: {}", e))?;
+        socket.connect(target)
+            .await
+            .map_err(|e| format!("Failed connect to {}: {}", address, e))?;
+
+        // Send Payload
+        debug!("[UDP] Sending datagram to {}...", address);
+        socket.send(&data)
+            .await
+            .map_err(|e| format!("Failed send message: {}", e))?;
+
+        // Wait one datagram (Logic Timeout)
+        let mut buffer = vec![0u8; options.read_bytes.unwrap_or(65_535).min(65_535)];
+        let (reply, stop_reason) = match timeout_at(deadline, socket.recv(&mut buffer)).await {
+            Ok(Ok(n)) => (buffer[..n].to_vec(), "datagram"),
+            Ok(Err(e)) => return Err(format!("[UDP] Error: {}", e)),
+            Err(_) => (Vec::new(), "timeout"),
+        };
+        let total_ms = started.elapsed().as_millis() as u64;
+
+        Ok(probe_result("udp", &address, &target.to_string(), None, total_ms, data.len(), &reply, stop_reason))
+    }
+}
+
+/// Payload bytes by encoding (text, hex, base64)
+pub fn decode_payload(payload: &Option<String>, encoding: &PayloadEncoding) -> Result<Vec<u8>, String> {
+    let Some(payload) = payload else {
+        return Ok(Vec::new());
+    };
+
+    match encoding {
+        PayloadEncoding::Text => Ok(payload.as_bytes().to_vec()),
+        PayloadEncoding::Base64 => STANDARD.decode(payload.trim())
+            .map_err(|e| format!("Invalid base64 payload: {}", e)),
+        PayloadEncoding::Hex => {
+            let hex: String = payload.chars().filter(|c| !c.is_whitespace()).collect();
+            // from_str_radix alone accept a sign ("+f")
+            if !hex.chars().all(|c| c.is_ascii_hexdigit()) || !hex.len().is_multiple_of(2) {
+                return Err("Invalid hex payload: expected pairs of hex digits".to_string());
+            }
+            (0..hex.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|e| format!("Invalid hex payload: {}", e)))
+                .collect()
+        }
+    }
+}
+
+/// Endpoint: host:port or tcp://host:port / udp://host:port
+fn socket_address(target_url: &str, scheme: &str) -> Result<String, String> {
+    let address = target_url
+        .strip_prefix(&format!("{}://", scheme))
+        .unwrap_or(target_url)
+        .trim_end_matches('/');
+
+    match address.rsplit_once(':') {
+        Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => Ok(address.to_string()),
+        _ => Err(format!("Invalid {} endpoint, expected host:port: {}", scheme, target_url)),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn probe_result(protocol: &str, address: &str, peer: &str, connect_ms: Option<u64>, total_ms: u64, bytes_sent: usize, reply: &[u8], stop_reason: &str) -> FetchResult {
+    let (reply_text, encoding) = match std::str::from_utf8(reply) {
+        Ok(text) => (text.to_string(), "text"),
+        Err(_) => (STANDARD.encode(reply), "base64"),
+    };
+
+    let response = json!({
+        "protocol": protocol,
+        "address": address,
+        "peer": peer,
+        "connect_ms": connect_ms,
+        "total_ms": total_ms,
+        "bytes_sent": bytes_sent,
+        "bytes_received": reply.len(),
+        "stop_reason": stop_reason,
+        "encoding": encoding,
+        "reply": reply_text,
+    });
+
+    FetchResult {
+        status_code: 0,
+        headers: json!({ "protocol": protocol, "peer": peer }),
+        response: response.to_string(),
+        error: None,
+        meta: None,
+        content_type: None,
+        response_raw: None,
+    }
+}
@@ -0,0 +1,125 @@
+use reqwest::Url;
+use serde_json::{Value, json};
+
+/// Engine.IO (v4) packet with decoded Socket.IO message
+#[derive(Debug, Clone, PartialEq)]
+pub enum Packet {
+    Open(Value),
+    Close,
+    Ping,
+    Pong,
+    Connect { namespace: String, data: Value },
+    Disconnect { namespace: String },
+    Event { namespace: String, id: Option<u64>, event: String, args: Vec<Value> },
+    Ack { namespace: String, id: Option<u64>, args: Vec<Value> },
+    ConnectError { namespace: String, data: Value },
+    Other(String),
+}
+
+/// Endpoint to Engine.IO websocket url: ws(s)://host/socket.io/?EIO=4&transport=websocket
+pub fn socketio_url(target_url: &str, path: &Option<String>) -> Result<String, String> {
+    let mut url = Url::parse(target_url).map_err(|e| format!("Invalid URL or Request: {}", e))?;
+    let scheme = match url.scheme() {
+        "http" | "ws" => "ws",
+        "https" | "wss" => "wss",
+        other => return Err(format!("Invalid socketio scheme: {}", other)),
+    };
+    url.set_scheme(scheme).map_err(|_| format!("Invalid socketio scheme: {}", scheme))?;
+
+    // Explicit path wins over endpoint path, default only for bare host
+    match path {
+        Some(path) => url.set_path(path),
+        None if url.path() == "/" || url.path().is_empty() => url.set_path("/socket.io/"),
+        None => {}
+    }
+    url.query_pairs_mut()
+        .append_pair("EIO", "4")
+        .append_pair("transport", "websocket");
+
+    Ok(url.to_string())
+}
+
+pub fn parse_packet(text: &str) -> Packet {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some('0') => Packet::Open(serde_json::from_str(chars.as_str()).unwrap_or(Value::Null)),
+        Some('1') => Packet::Close,
+        Some('2') => Packet::Ping,
+        Some('3') => Packet::Pong,
+        Some('4') => parse_message(chars.as_str()),
+        _ => Packet::Other(text.to_string()),
+    }
+}
+
+fn parse_message(message: &str) -> Packet {
+    let mut chars = message.chars();
+    let Some(packet_type) = chars.next() else {
+        return Packet::Other(message.to_string());
+    };
+    let mut rest = chars.as_str();
+
+    // Binary attachments count "<n>-"
+    if matches!(packet_type, '5' | '6') && let Some((count, tail)) = rest.split_once('-')
+        && count.chars().all(|c| c.is_ascii_digit()) {
+        rest = tail;
+    }
+
+    let mut namespace = "/".to_string();
+    if rest.starts_with('/') {
+        let (nsp, tail) = rest.split_once(',').unwrap_or((rest, ""));
+        namespace = nsp.to_string();
+        rest = tail;
+    }
+
+    let digits = rest.chars().take_while(|c| c.is_ascii_digit()).count();
+    let id = rest[..digits].parse::<u64>().ok();
+    let data: Value = serde_json::from_str(&rest[digits..]).unwrap_or(Value::Null);
+
+    match packet_type {
+        '0' => Packet::Connect { namespace, data },
+        '1' => Packet::Disconnect { namespace },
+        '2' | '5' => {
+            let mut args = match data {
+                Value::Array(args) => args,
+                _ => Vec::new(),
+            };
+            if args.is_empty() {
+                return Packet::Other(message.to_string());
+            }
+            let event = match args.remove(0) {
+                Value::String(event) => event,
+                other => other.to_string(),
+            };
+            Packet::Event { namespace, id, event, args }
+        }
+        '3' | '6' => Packet::Ack {
+            namespace,
+            id,
+            args: match data {
+                Value::Array(args) => args,
+                _ => Vec::new(),
+            },
+        },
+        '4' => Packet::ConnectError { namespace, data },
+        _ => Packet::Other(message.to_string()),
+    }
+}
+
+fn namespace_prefix(namespace: &str) -> String {
+    if namespace == "/" { String::new() } else { format!("{},", namespace) }
+}
+
+pub fn connect_packet(namespace: &str, auth: &Option<Value>) -> String {
+    let auth = auth.as_ref().map(|a| a.to_string()).unwrap_or_default();
+    format!("40{}{}", namespace_prefix(namespace), auth)
+}
+
+pub fn event_packet(namespace: &str, event: &str, args: &[Value]) -> String {
+    let mut data = vec![json!(event)];
+    data.extend(args.iter().cloned());
+    format!("42{}{}", namespace_prefix(namespace), Value::Array(data))
+}
+
+pub fn disconnect_packet(namespace: &str) -> String {
+    format!("41{}", namespace_prefix(namespace))
+}
@@ -0,0 +1,90 @@
+use serde_json::{Value, json};
+use sqlx::{Connection, PgConnection, Row, postgres::PgConnectOptions};
+use std::{str::FromStr, time::{Duration, Instant}};
+use tokio::time::timeout;
+use crate::models::fetch::{ApiCredential, FetchResult, SqlOptions};
+use tracing::debug;
+
+/// Connection string from credential `database_url` (secret, not in header set)
+pub async fn request_response(statement: &Option<String>, options: &Option<Value>, credential: &Option<ApiCredential>) -> Result<FetchResult, String> {
+    let options = SqlOptions::parse(options)?;
+    let statement = statement.as_deref().unwrap_or("").trim().trim_end_matches(';').trim();
+    if statement.is_empty() {
+        return Err("SQL statement is required".to_string());
+    }
+    let database_url = credential
+        .as_ref()
+        .and_then(|c| c.database_url.as_deref())
+        .ok_or("Credential with database_url is required for sql fetch".to_string())?;
+
+    let connect_options = PgConnectOptions::from_str(database_url)
+        .map_err(|_| "Invalid database_url in credential".to_string())?
+        .application_name("teknohole-scheduler");
+    let database = connect_options.get_database().unwrap_or("").to_string();
+    let limit_duration = Duration::from_secs(options.statement_timeout);
+
+    // Connect
+    let started = Instant::now();
+    let mut conn = timeout(limit_duration, PgConnection::connect_with(&connect_options))
+        .await
+        .map_err(|_| "Failed connect to database: timeout".to_string())?
+        .map_err(|e| format!("Failed connect to database: {}", e))?;
+    let connect_ms = started.elapsed().as_millis() as u64;
+
+    let rows = timeout(limit_duration + Duration::from_secs(5), run_read_only(&mut conn, statement, &options)).await;
+    let _ = conn.close().await;
+    let mut rows = rows.map_err(|_| "SQL statement timeout".to_string())??;
+    let total_ms = started.elapsed().as_millis() as u64;
+
+    let truncated = rows.len() as i64 > options.row_limit;
+    rows.truncate(options.row_limit as usize);
+    debug!("[SQL] {} rows from {} (truncated: {})", rows.len(), database, truncated);
+
+    let headers = json!({
+        "database": database,
+        "row_count": rows.len(),
+        "truncated": truncated,
+        "row_limit": options.row_limit,
+        "connect_ms": connect_ms,
+        "total_ms": total_ms,
+    });
+
+    Ok(FetchResult {
+        status_code: 0,
+        headers,
+        response: Value::Array(rows).to_string(),
+        error: None,
+        meta: None,
+        content_type: None,
+        response_raw: None,
+    })
+}
+
+/// Run statement inside READ ONLY transaction, rows as json objects (column order kept)
+async fn run_read_only(conn: &mut PgConnection, statement: &str, options: &SqlOptions) -> Result<Vec<Value>, String> {
+    let mut tx = conn.begin()
+        .await
+        .map_err(|e| format!("Failed begin transaction: {}", e))?;
+
+    sqlx::query("SET TRANSACTION READ ONLY")
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed set read only: {}", e))?;
+    sqlx::query(&format!("SET LOCAL statement_timeout = {}", options.statement_timeout * 1000))
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed set statement timeout: {}", e))?;
+
+    // One extra row to detect truncation
+    let wrapped = format!("SELECT row_to_json(q) AS row FROM ({}) AS q LIMIT {}", statement, options.row_limit + 1);
+    let rows = sqlx::query(&wrapped)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| format!("SQL error: {}", e))?;
+
+    let _ = tx.rollback().await;
+
+    rows.iter()
+        .map(|r| r.try_get::<Value, _>("row").map_err(|e| format!("Failed decode row: {}", e)))
+        .collect()
+}
@@ -0,0 +1,243 @@
+use reqwest::{Method, header::{ACCEPT, CACHE_CONTROL, HeaderValue}};
+use serde_json::{Value, json};
+use std::time::Duration;
+use chrono::Utc;
+use tokio::time::sleep;
+use crate::{jobs::http::{HttpClients, read_body, send_with_redirects}, models::fetch::{ApiCredential, ApiMethod, FetchResult, HttpOptions}, utils::reqwest::{headermap_to_json, json_to_headermap}};
+use tracing::debug;
+
+/// Extra time over the listen window before the request itself times out,
+/// the window end always stop the stream first
+const TIMEOUT_GRACE: Duration = Duration::from_secs(5);
+
+#[derive(Clone)]
+pub struct SseJobs {
+    timeout_duration: Duration,
+}
+
+/// Result of one sse listen window
+pub struct SseResult {
+    pub result: FetchResult,
+    pub last_event_id: Option<String>,
+}
+
+impl SseJobs {
+    pub fn new(timeout: u64) -> Self {
+        Self {
+            timeout_duration: Duration::from_secs(timeout),
+        }
+    }
+
+    /// Listen for events on the shared http client (proxy, TLS, credential from options).
+    /// Window is the http options timeout, default SSE_TIMEOUT, collected bytes capped by max_body_bytes.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn request_response(&self, http_clients: &HttpClients, target_url: &str, method: &Option<ApiMethod>, payload: &Option<String>, options: &Option<Value>, headers: Option<Value>, credential: &Option<ApiCredential>, last_event_id: &Option<String>) -> Result<SseResult, String> {
+        let options = HttpOptions::parse(options)?;
+        let window = options.timeout.map(Duration::from_secs).unwrap_or(self.timeout_duration);
+        let http_client = http_clients.get(&options, credential)?;
+
+        let mut headers_map = json_to_headermap(headers).await;
+        headers_map.insert(ACCEPT, HeaderValue::from_static("text/event-stream"));
+        headers_map.insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+        if let Some(id) = last_event_id
+            && let Ok(value) = HeaderValue::from_str(id) {
+            debug!("[SSE] Resume from event id {}", id);
+            headers_map.insert("last-event-id", value);
+        }
+
+        let req_method = match method {
+            Some(ApiMethod::Post) => Method::POST,
+            Some(ApiMethod::Put) => Method::PUT,
+            Some(ApiMethod::Patch) => Method::PATCH,
+            _ => Method::GET,
+        };
+
+        let sleep_timer = sleep(window);
+        tokio::pin!(sleep_timer);
+
+        // Connect, client total timeout replaced so it does not cut the stream
+        let sending = send_with_redirects(&http_client, req_method, target_url, headers_map, &[], options.max_redirects, |request_builder| {
+            let request_builder = request_builder.timeout(window + TIMEOUT_GRACE);
+            Ok(match payload {
+                Some(payload) if !payload.is_empty() => request_builder.body(payload.clone()),
+                _ => request_builder,
+            })
+        });
+        let (mut response, redirects) = tokio::select! {
+            res = sending => res.map_err(|e| format!("Failed connect to event stream: {}", e))?,
+            _ = &mut sleep_timer => return Err("Failed connect to event stream: timeout".to_string()),
+        };
+
+        let final_url = response.url().to_string();
+        let status_obj = response.status();
+        let status_code = status_obj.as_u16() as i16;
+        let response_headers_json = headermap_to_json(response.headers());
+        let is_event_stream = response.headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("text/event-stream"));
+
+        if !status_obj.is_success() || !is_event_stream {
+            let body = tokio::select! {
+                body = read_body(response, http_clients.body_cap(&options)) => body?,
+                _ = &mut sleep_timer => return Err("Failed response message: timeout".to_string()),
+            };
+            return Ok(SseResult {
+                result: FetchResult {
+                    status_code,
+                    headers: response_headers_json,
+                    response: body.text,
+                    error: Some(format!("Not an event stream response [{}]", status_code)),
+                    meta: Some(json!({ "url": final_url, "redirects": redirects, "body": body.meta })),
+                    content_type: body.content_type,
+                    response_raw: body.raw,
+                },
+                last_event_id: last_event_id.clone(),
+            });
+        }
+
+        // Collect events (Logic Timeout)
+        let max_bytes = http_clients.body_cap(&options);
+        let mut parser = SseParser::new(last_event_id.clone());
+        let mut collected_events: Vec<Value> = Vec::new();
+        let mut read = 0;
+        let mut truncated = false;
+
+        loop {
+            tokio::select! {
+                chunk = response.chunk() => {
+                    match chunk {
+                        Ok(Some(bytes)) => {
+                            read += bytes.len();
+                            for event in parser.feed(&bytes) {
+                                debug!("[SSE] Collecting event...");
+                                collected_events.push(event);
+                            }
+                            if read >= max_bytes {
+                                debug!("[SSE] Stream cap {} bytes reached, stopping listener.", max_bytes);
+                                truncated = true;
+                                break;
+                            }
+                        }
+                        Ok(None) => {
+                            debug!("[SSE] Stream closed by server.");
+                            break;
+                        }
+                        Err(e) => return Err(format!("[SSE] Error: {}", e)),
+                    }
+                }
+                _ = &mut sleep_timer => {
+                    debug!("[SSE] Timeout reached, stopping listener.");
+                    break;
+                }
+            }
+        }
+
+        if collected_events.is_empty() {
+            debug!("[SSE] No events received during the timeout period");
+        }
+
+        Ok(SseResult {
+            result: FetchResult {
+                status_code,
+                headers: response_headers_json,
+                response: Value::Array(collected_events).to_string(),
+                error: None,
+                meta: Some(json!({ "url": final_url, "redirects": redirects, "body": { "size": read, "truncated": truncated } })),
+                content_type: None,
+                response_raw: None,
+            },
+            last_event_id: parser.last_event_id,
+        })
+    }
+}
+
+/// Incremental text/event-stream parser (WHATWG event stream interpretation)
+pub struct SseParser {
+    buffer: Vec<u8>,
+    event_type: String,
+    data: String,
+    retry: Option<u64>,
+    /// Last event ID buffer, id of every dispatched event
+    pub last_event_id: Option<String>,
+}
+
+impl SseParser {
+    pub fn new(last_event_id: Option<String>) -> Self {
+        Self {
+            buffer: Vec::new(),
+            event_type: String::new(),
+            data: String::new(),
+            retry: None,
+            last_event_id,
+        }
+    }
+
+    /// Feed raw bytes, returns events completed by this chunk
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<Value> {
+        self.buffer.extend_from_slice(bytes);
+        let mut events = Vec::new();
+
+        while let Some(pos) = self.buffer.iter().position(|b| *b == b'\n' || *b == b'\r') {
+            // CR at the end of chunk may be followed by LF in the next chunk
+            if self.buffer[pos] == b'\r' && pos + 1 == self.buffer.len() {
+                break;
+            }
+            let skip = if self.buffer[pos] == b'\r' && self.buffer[pos + 1] == b'\n' { 2 } else { 1 };
+            let line: Vec<u8> = self.buffer.drain(..pos + skip).take(pos).collect();
+            let line = String::from_utf8_lossy(&line);
+
+            if let Some(event) = self.process_line(&line) {
+                events.push(event);
+            }
+        }
+
+        events
+    }
+
+    fn process_line(&mut self, line: &str) -> Option<Value> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+        if line.starts_with(':') {
+            return None;
+        }
+
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+
+        match field {
+            "event" => self.event_type = value.to_string(),
+            "data" => {
+                self.data.push_str(value);
+                self.data.push('\n');
+            }
+            "id" if !value.contains('\0') => self.last_event_id = Some(value.to_string()),
+            "retry" => self.retry = value.parse::<u64>().ok(),
+            _ => {}
+        }
+
+        None
+    }
+
+    fn dispatch(&mut self) -> Option<Value> {
+        let retry = self.retry.take();
+        let event_type = std::mem::take(&mut self.event_type);
+        if self.data.is_empty() {
+            return None;
+        }
+
+        let mut data = std::mem::take(&mut self.data);
+        data.pop();
+
+        Some(json!({
+            "id": self.last_event_id.clone(),
+            "event": if event_type.is_empty() { "message".to_string() } else { event_type },
+            "data": data,
+            "retry": retry,
+            "received_at": Utc::now(),
+        }))
+    }
+}
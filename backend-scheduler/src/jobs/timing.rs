@@ -0,0 +1,162 @@
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use serde_json::{Value, json};
+use std::{cell::RefCell, future::Future, pin::Pin, sync::Arc, task::{Context, Poll}, time::{Duration, Instant}};
+use tokio_rustls::rustls::{ClientConfig, SignatureScheme, client::ResolvesClientCert, sign::CertifiedKey};
+use tower::{Layer, Service};
+
+tokio::task_local! {
+    static PHASES: RefCell<Phases>;
+}
+
+/// Connection phase marks of one request, set by the client hooks below while `measure` runs.
+/// Connect of pooled connection is not run, all marks stay empty.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Phases {
+    pub dns_start: Option<Instant>,
+    pub dns_end: Option<Instant>,
+    pub connect_start: Option<Instant>,
+    /// TCP connected, TLS handshake started (https only)
+    pub tcp_connected: Option<Instant>,
+    pub connect_end: Option<Instant>,
+    /// Handshake complete minus TCP connected, set by the connector layer
+    pub tls: Option<Duration>,
+}
+
+impl Phases {
+    /// `{dns_ms, connect_ms, tls_ms, ttfb_ms, total_ms, size_bytes, reused}` for fetch meta,
+    /// phase not run (IP host, plain http, reused connection) is null
+    pub fn to_json(&self, ttfb: Duration, total: Duration, size: usize) -> Value {
+        // TCP start after DNS, connector itself start before resolving
+        let tcp_start = match (self.dns_end, self.connect_start) {
+            (Some(dns_end), Some(connect_start)) => Some(dns_end.max(connect_start)),
+            (dns_end, connect_start) => dns_end.or(connect_start),
+        };
+
+        json!({
+            "dns_ms": span_ms(self.dns_start, self.dns_end),
+            "connect_ms": span_ms(tcp_start, self.tcp_connected.or(self.connect_end)),
+            "tls_ms": self.tls.map(ms),
+            "ttfb_ms": ms(ttfb),
+            "total_ms": ms(total),
+            "size_bytes": size,
+            "reused": self.connect_start.is_none(),
+        })
+    }
+}
+
+/// Run request future with an empty phase slot, return its output and recorded phases
+pub async fn measure<F: Future>(future: F) -> (F::Output, Phases) {
+    PHASES.scope(RefCell::new(Phases::default()), async {
+        let output = future.await;
+        (output, PHASES.with(|phases| *phases.borrow()))
+    }).await
+}
+
+/// Clear marks before next request (redirect hop)
+pub fn reset() {
+    mark(|phases| *phases = Phases::default());
+}
+
+/// Milliseconds with microsecond precision
+pub fn ms(duration: Duration) -> f64 {
+    (duration.as_secs_f64() * 1_000_000.0).round() / 1000.0
+}
+
+fn span_ms(start: Option<Instant>, end: Option<Instant>) -> Option<f64> {
+    Some(ms(end?.saturating_duration_since(start?)))
+}
+
+/// Outside `measure` (background connect of the pool) nothing is recorded
+fn mark(f: impl FnOnce(&mut Phases)) {
+    let _ = PHASES.try_with(|phases| f(&mut phases.borrow_mut()));
+}
+
+/// System resolver (getaddrinfo) recording DNS time
+pub struct TimedResolver;
+
+impl Resolve for TimedResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            mark(|phases| phases.dns_start = Some(Instant::now()));
+            let addrs = tokio::net::lookup_host((host, 0)).await?;
+            mark(|phases| phases.dns_end = Some(Instant::now()));
+
+            Ok::<Addrs, Box<dyn std::error::Error + Send + Sync>>(Box::new(addrs))
+        })
+    }
+}
+
+/// Connector layer recording connection setup (DNS + TCP + TLS), TLS time derived when connected
+#[derive(Clone)]
+pub struct TimedConnectLayer;
+
+impl<S> Layer<S> for TimedConnectLayer {
+    type Service = TimedConnect<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TimedConnect { inner }
+    }
+}
+
+#[derive(Clone)]
+pub struct TimedConnect<S> {
+    inner: S,
+}
+
+impl<S, R> Service<R> for TimedConnect<S>
+where
+    S: Service<R>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: R) -> Self::Future {
+        let connecting = self.inner.call(request);
+        Box::pin(async move {
+            mark(|phases| phases.connect_start = Some(Instant::now()));
+            let conn = connecting.await;
+            mark(|phases| {
+                let connect_end = Instant::now();
+                phases.connect_end = Some(connect_end);
+                phases.tls = phases.tcp_connected.map(|tcp_connected| connect_end.saturating_duration_since(tcp_connected));
+            });
+
+            conn
+        })
+    }
+}
+
+/// Wrap client cert resolver of TLS config, rustls ask it once when starting every handshake
+/// (TLS 1.2 / 1.3, new or resumed session), right after TCP connected
+pub fn mark_tcp_connected(config: &mut ClientConfig) {
+    config.client_auth_cert_resolver = Arc::new(TcpConnectedMark { inner: config.client_auth_cert_resolver.clone() });
+}
+
+#[derive(Debug)]
+struct TcpConnectedMark {
+    inner: Arc<dyn ResolvesClientCert>,
+}
+
+impl ResolvesClientCert for TcpConnectedMark {
+    fn resolve(&self, root_hint_subjects: &[&[u8]], sigschemes: &[SignatureScheme]) -> Option<Arc<CertifiedKey>> {
+        self.inner.resolve(root_hint_subjects, sigschemes)
+    }
+
+    fn only_raw_public_keys(&self) -> bool {
+        self.inner.only_raw_public_keys()
+    }
+
+    fn has_certs(&self) -> bool {
+        mark(|phases| {
+            phases.tcp_connected.get_or_insert_with(Instant::now);
+        });
+        self.inner.has_certs()
+    }
+}
@@ -0,0 +1,213 @@
+use chrono::{DateTime, Utc};
+use serde_json::{Value, json};
+use std::{sync::{Arc, Mutex}, time::{Duration, Instant}};
+use tokio::{net::TcpStream, time::timeout};
+use tokio_rustls::{TlsConnector, rustls::{self, ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme, client::{WebPkiServerVerifier, danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier}}, crypto::CryptoProvider, pki_types::{CertificateDer, ServerName, UnixTime}}};
+use x509_parser::{extensions::GeneralName, prelude::{FromDer, X509Certificate}};
+use crate::models::fetch::{FetchResult, TlsOptions};
+use tracing::debug;
+
+
+#[derive(Clone)]
+pub struct TlsJobs {
+    timeout_duration: Duration,
+    provider: Arc<CryptoProvider>,
+    roots: Arc<RootCertStore>,
+}
+
+impl TlsJobs {
+    pub fn new(timeout: u64) -> Self {
+        let roots = RootCertStore::from_iter(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+
+        Self {
+            timeout_duration: Duration::from_secs(timeout),
+            provider: Arc::new(rustls::crypto::aws_lc_rs::default_provider()),
+            roots: Arc::new(roots),
+        }
+    }
+
+    pub async fn request_response(&self, target_url: &str, options: &Option<Value>) -> Result<FetchResult, String> {
+        let options = TlsOptions::parse(options)?;
+        let timeout_duration = options.timeout.map(Duration::from_secs).unwrap_or(self.timeout_duration);
+        let (host, port) = tls_address(target_url)?;
+        let sni = options.server_name.clone().unwrap_or(host.clone());
+        let server_name = ServerName::try_from(sni.clone())
+            .map_err(|e| format!("Invalid TLS server name {}: {}", sni, e))?;
+
+        // Verify with webpki roots, but keep handshake going to inspect invalid chain
+        let webpki = WebPkiServerVerifier::builder_with_provider(self.roots.clone(), self.provider.clone())
+            .build()
+            .map_err(|e| format!("Failed build TLS verifier: {}", e))?;
+        let verifier = Arc::new(RecordingVerifier { inner: webpki, error: Mutex::new(None) });
+
+        let config = ClientConfig::builder_with_provider(self.provider.clone())
+            .with_safe_default_protocol_versions()
+            .map_err(|e| format!("Failed build TLS config: {}", e))?
+            .dangerous()
+            .with_custom_certificate_verifier(verifier.clone())
+            .with_no_client_auth();
+        let connector = TlsConnector::from(Arc::new(config));
+
+        // Connect & handshake
+        let started = Instant::now();
+        let stream = timeout(timeout_duration, TcpStream::connect((host.as_str(), port)))
+            .await
+            .map_err(|_| format!("Failed connect to {}:{}: timeout", host, port))?
+            .map_err(|e| format!("Failed connect to {}:{}: {}", host, port, e))?;
+        let connect_ms = started.elapsed().as_millis() as u64;
+
+        let tls_stream = timeout(timeout_duration, connector.connect(server_name, stream))
+            .await
+            .map_err(|_| format!("TLS handshake with {}:{} timeout", host, port))?
+            .map_err(|e| format!("TLS handshake with {}:{} failed: {}", host, port, e))?;
+        let handshake_ms = started.elapsed().as_millis() as u64 - connect_ms;
+
+        let (_, connection) = tls_stream.get_ref();
+        let protocol = connection.protocol_version().map(|v| format!("{:?}", v));
+        let cipher = connection.negotiated_cipher_suite().map(|c| format!("{:?}", c.suite()));
+        let alpn = connection.alpn_protocol().map(|p| String::from_utf8_lossy(p).to_string());
+        let chain: Vec<Value> = connection.peer_certificates()
+            .unwrap_or_default()
+            .iter()
+            .map(|der| certificate_to_json(der.as_ref()))
+            .collect();
+        let verify_error = verifier.error.lock().map(|e| e.clone()).unwrap_or(None);
+        debug!("[TLS] Handshake {}:{} done, {} certificate", host, port, chain.len());
+
+        let days_remaining = chain.first().and_then(|c| c["days_remaining"].as_i64());
+        let mut failures: Vec<String> = Vec::new();
+        match days_remaining {
+            Some(days) if days < options.expiry_days => {
+                failures.push(format!("Certificate expires in {} days (threshold {} days)", days, options.expiry_days));
+            }
+            None => failures.push("No peer certificate".to_string()),
+            _ => {}
+        }
+        if options.verify && let Some(e) = &verify_error {
+            failures.push(format!("Certificate verification failed: {}", e));
+        }
+
+        let response = json!({
+            "host": host,
+            "port": port,
+            "server_name": sni,
+            "protocol": protocol,
+            "cipher": cipher,
+            "alpn": alpn,
+            "connect_ms": connect_ms,
+            "handshake_ms": handshake_ms,
+            "verified": verify_error.is_none(),
+            "verify_error": verify_error,
+            "days_remaining": days_remaining,
+            "expiry_days": options.expiry_days,
+            "chain": chain,
+        });
+
+        Ok(FetchResult {
+            status_code: 0,
+            headers: json!({ "protocol": protocol, "cipher": cipher }),
+            response: response.to_string(),
+            error: if failures.is_empty() { None } else { Some(failures.join("; ")) },
+            meta: None,
+            content_type: None,
+            response_raw: None,
+        })
+    }
+}
+
+/// Endpoint: host, host:port, tls://host:port or https://host[:port]/...
+fn tls_address(target_url: &str) -> Result<(String, u16), String> {
+    let address = target_url
+        .split_once("://")
+        .map(|(_, rest)| rest)
+        .unwrap_or(target_url);
+    let address = address.split('/').next().unwrap_or(address);
+
+    // [ipv6]:port
+    if let Some(rest) = address.strip_prefix('[') {
+        let (host, port) = rest.split_once(']').ok_or(format!("Invalid TLS endpoint: {}", target_url))?;
+        let port = match port.strip_prefix(':') {
+            Some(p) => p.parse::<u16>().map_err(|_| format!("Invalid TLS port: {}", target_url))?,
+            None => 443,
+        };
+        return Ok((host.to_string(), port));
+    }
+
+    match address.rsplit_once(':') {
+        Some((host, port)) => {
+            let port = port.parse::<u16>().map_err(|_| format!("Invalid TLS port: {}", target_url))?;
+            Ok((host.to_string(), port))
+        }
+        None if !address.is_empty() => Ok((address.to_string(), 443)),
+        None => Err(format!("Invalid TLS endpoint: {}", target_url)),
+    }
+}
+
+fn certificate_to_json(der: &[u8]) -> Value {
+    let Ok((_, cert)) = X509Certificate::from_der(der) else {
+        return json!({ "error": "Failed parse certificate" });
+    };
+
+    let not_before = DateTime::<Utc>::from_timestamp(cert.validity().not_before.timestamp(), 0);
+    let not_after = DateTime::<Utc>::from_timestamp(cert.validity().not_after.timestamp(), 0);
+    let days_remaining = not_after.map(|t| (t - Utc::now()).num_days());
+
+    let mut sans: Vec<String> = Vec::new();
+    if let Ok(Some(ext)) = cert.subject_alternative_name() {
+        for name in &ext.value.general_names {
+            match name {
+                GeneralName::DNSName(dns) => sans.push(dns.to_string()),
+                GeneralName::IPAddress(ip) => match ip.len() {
+                    4 => sans.push(std::net::Ipv4Addr::new(ip[0], ip[1], ip[2], ip[3]).to_string()),
+                    16 => {
+                        let octets: [u8; 16] = (*ip).try_into().unwrap_or([0; 16]);
+                        sans.push(std::net::Ipv6Addr::from(octets).to_string());
+                    }
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+    }
+
+    json!({
+        "subject": cert.subject().to_string(),
+        "issuer": cert.issuer().to_string(),
+        "serial": cert.raw_serial_as_string(),
+        "sans": sans,
+        "not_before": not_before,
+        "not_after": not_after,
+        "days_remaining": days_remaining,
+        "signature_algorithm": cert.signature_algorithm.algorithm.to_id_string(),
+    })
+}
+
+/// Record webpki verification error instead of aborting the handshake
+#[derive(Debug)]
+struct RecordingVerifier {
+    inner: Arc<WebPkiServerVerifier>,
+    error: Mutex<Option<String>>,
+}
+
+impl ServerCertVerifier for RecordingVerifier {
+    fn verify_server_cert(&self, end_entity: &CertificateDer<'_>, intermediates: &[CertificateDer<'_>], server_name: &ServerName<'_>, ocsp_response: &[u8], now: UnixTime) -> Result<ServerCertVerified, rustls::Error> {
+        if let Err(e) = self.inner.verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)
+            && let Ok(mut error) = self.error.lock() {
+            *error = Some(e.to_string());
+        }
+
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(&self, message: &[u8], cert: &CertificateDer<'_>, dss: &DigitallySignedStruct) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(&self, message: &[u8], cert: &CertificateDer<'_>, dss: &DigitallySignedStruct) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
@@ -1,12 +1,19 @@
 use futures_util::{SinkExt, StreamExt};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use chrono::Utc;
 use serde_json::{Value, json};
-use std::{collections::HashMap, time::Duration};
-use tokio::time::sleep;
-use tokio_tungstenite::{connect_async, tungstenite::{client::IntoClientRequest, protocol::Message}};
-use crate::utils::reqwest::json_to_headermap;
-use crate::models::fetch::FetchResult;
+use regex::Regex;
+use std::{collections::HashMap, sync::Arc, time::{Duration, Instant}};
+use tokio::{net::TcpStream, time::{self, sleep, timeout_at}};
+use tokio_tungstenite::{Connector, MaybeTlsStream, WebSocketStream, connect_async_tls_with_config, tungstenite::{client::IntoClientRequest, protocol::{CloseFrame, Message}}};
+use crate::utils::{json_path, reqwest::json_to_headermap, tls::client_config};
+use crate::jobs::{timing, socketio::{Packet, connect_packet, disconnect_packet, event_packet, parse_packet, socketio_url}};
+use crate::models::fetch::{ApiCredential, FetchResult, SocketIoOptions, WsMode, WsOptions, WsStep};
 use tracing::debug;
 
+/// Deadline of a whole script run, connect included
+const SCRIPT_DEADLINE: Duration = Duration::from_secs(300);
+
 
 #[derive(Clone)]
 pub struct WsJobs {
@@ -20,28 +27,17 @@ impl WsJobs {
         }
     }
 
-    pub async fn request_response(&self, target_url: &str, payload: &Option<String>, headers: Option<Value>) -> Result<FetchResult, String> {
-        let mut request = target_url
-            .into_client_request()
-            .map_err(|e| format!("Invalid URL or Request: {}", e))?;
-
-        let header_map = json_to_headermap(headers).await;
-        request.headers_mut().extend(header_map);
-
-        // Connect
-        let (ws_stream, response) = connect_async(request)
-            .await
-            .map_err(|e| format!("Failed connect to websocket: {}", e))?;
-
-        let status_obj = response.status();
-        let status_code = status_obj.as_u16() as i16;
-        let mut server_headers = HashMap::new();
-
-        for (key, value) in response.headers() {
-            let val_str = value.to_str().unwrap_or("").to_string();
-            server_headers.insert(key.to_string(), val_str);
+    pub async fn request_response(&self, target_url: &str, payload: &Option<String>, options: &Option<Value>, headers: Option<Value>, credential: &Option<ApiCredential>) -> Result<FetchResult, String> {
+        let options = WsOptions::parse(options)?;
+        if options.mode == WsMode::Socketio {
+            return self.socketio_request_response(target_url, &options.socketio, headers, credential).await;
         }
 
+        // Connect, script mode share one deadline with its steps
+        let started = Instant::now();
+        let deadline = time::Instant::now() + if options.steps.is_empty() { self.timeout_duration } else { SCRIPT_DEADLINE };
+        let (ws_stream, status_code, server_headers) = connect(target_url, headers, credential, deadline).await?;
+        let connected = started.elapsed();
         let (mut write, mut read) = ws_stream.split();
 
         // Send Payload
@@ -55,45 +51,358 @@ impl WsJobs {
             debug!("[WS] No payload provided, directly listening...");
         }
 
-        // Collect response (Logic Timeout)
-        let mut collected_messages: Vec<String> = Vec::new();
-        let sleep_timer = sleep(self.timeout_duration);
-        tokio::pin!(sleep_timer);
+        let mut collected_messages: Vec<Value> = Vec::new();
+        let mut close: Option<Value> = None;
+        let mut error: Option<String> = None;
+        let mut script: Option<Value> = None;
+        let mut first_message: Option<Duration> = None;
 
-        loop {
-            tokio::select! {
-                msg = read.next() => {
-                    match msg {
-                        Some(Ok(Message::Text(text))) => {
-                            debug!("[WS] Collecting websocket response...");
-                            collected_messages.push(text.to_string());
-                        }
-                        Some(Ok(Message::Binary(_))) => {
-                            collected_messages.push("[Binary Data]".to_string());
+        let stop_reason = if options.steps.is_empty() {
+            // Collect response (Logic Timeout)
+            let sleep_timer = sleep(self.timeout_duration);
+            tokio::pin!(sleep_timer);
+
+            loop {
+                tokio::select! {
+                    msg = read.next() => {
+                        match msg {
+                            Some(Ok(Message::Close(frame))) => {
+                                debug!("[WS] Close frame received");
+                                close = Some(close_to_json(frame));
+                            }
+                            Some(Ok(Message::Frame(_))) => {}
+                            Some(Ok(message)) => {
+                                debug!("[WS] Collecting websocket response...");
+                                first_message.get_or_insert_with(|| started.elapsed());
+                                collected_messages.push(message_to_json(message));
+                            }
+                            Some(Err(e)) => return Err(format!("[WS] Error: {}", e)),
+                            None => break if close.is_some() { "close" } else { "eof" },
                         }
-                        Some(Err(e)) => return Err(format!("[WS] Error: {}", e)),
-                        None => break,
-                        _ => {}
+                    }
+                    _ = &mut sleep_timer => {
+                        debug!("[WS] Timeout reached, stopping listener.");
+                        break "timeout";
                     }
                 }
-                _ = &mut sleep_timer => {
-                    debug!("[WS] Timeout reached, stopping listener.");
-                    break;
+            }
+        } else {
+            // Run script steps, stop on first unmet expectation
+            let mut saved: HashMap<String, String> = HashMap::new();
+            let mut completed = 0;
+            let mut stop_reason = "script";
+
+            'script: for (i, step) in options.steps.iter().enumerate() {
+                let step_no = i + 1;
+                match step {
+                    WsStep::Send { message } => {
+                        debug!("[WS] Step {}: send", step_no);
+                        let sent = timeout_at(deadline, write.send(Message::Text(render_template(message, &saved).into()))).await;
+                        match sent {
+                            Ok(sent) => sent.map_err(|e| format!("Failed send message: {}", e))?,
+                            Err(_) => {
+                                error = Some(format!("Step {} exceeded script deadline", step_no));
+                                stop_reason = "timeout";
+                                break 'script;
+                            }
+                        }
+                    }
+                    WsStep::Sleep { ms } => {
+                        debug!("[WS] Step {}: sleep {}ms", step_no, ms);
+                        if timeout_at(deadline, sleep(Duration::from_millis(*ms))).await.is_err() {
+                            error = Some(format!("Step {} exceeded script deadline", step_no));
+                            stop_reason = "timeout";
+                            break 'script;
+                        }
+                    }
+                    WsStep::Expect { pattern, json_path, equals, timeout, save } => {
+                        debug!("[WS] Step {}: expect", step_no);
+                        let pattern = match pattern {
+                            Some(p) => Some(Regex::new(p).map_err(|e| format!("Invalid pattern in step {}: {}", step_no, e))?),
+                            None => None,
+                        };
+                        let step_deadline = deadline.min(time::Instant::now() + timeout.map(Duration::from_secs).unwrap_or(self.timeout_duration));
+
+                        loop {
+                            let message = match timeout_at(step_deadline, read.next()).await {
+                                Err(_) => {
+                                    error = Some(format!("Step {} expect timeout", step_no));
+                                    stop_reason = "timeout";
+                                    break 'script;
+                                }
+                                Ok(None) => {
+                                    error = Some(format!("Connection closed before step {} matched", step_no));
+                                    stop_reason = "eof";
+                                    break 'script;
+                                }
+                                Ok(Some(Err(e))) => return Err(format!("[WS] Error: {}", e)),
+                                Ok(Some(Ok(Message::Close(frame)))) => {
+                                    close = Some(close_to_json(frame));
+                                    error = Some(format!("Connection closed before step {} matched", step_no));
+                                    stop_reason = "close";
+                                    break 'script;
+                                }
+                                Ok(Some(Ok(Message::Frame(_)))) => continue,
+                                Ok(Some(Ok(message))) => message,
+                            };
+                            first_message.get_or_insert_with(|| started.elapsed());
+
+                            let matched = match &message {
+                                Message::Text(text) => match_message(text, &pattern, json_path, equals, save, &mut saved),
+                                _ => false,
+                            };
+                            let mut message = message_to_json(message);
+                            if matched {
+                                message["step"] = json!(step_no);
+                            }
+                            collected_messages.push(message);
+                            if matched {
+                                break;
+                            }
+                        }
+                    }
                 }
+                completed = step_no;
             }
-        }
+
+            // Script done, end connection early
+            if close.is_none() {
+                let _ = timeout_at(deadline, write.send(Message::Close(None))).await;
+            }
+            script = Some(json!({
+                "total": options.steps.len(),
+                "completed": completed,
+                "saved": saved.keys().collect::<Vec<_>>(),
+            }));
+
+            stop_reason
+        };
 
         if collected_messages.is_empty() {
             debug!("[WS] No messages received during the timeout period");
         }
-        let response = collected_messages.join("\n---\n");
+        let response = json!({
+            "messages": collected_messages,
+            "close": close,
+            "stop_reason": stop_reason,
+            "script": script,
+        }).to_string();
 
         let result = FetchResult{
             status_code: status_code,
             headers: json!(server_headers),
-            response: response,
+            response,
+            error,
+            meta: Some(json!({ "timing": stream_timing(connected, first_message, started.elapsed()) })),
+            content_type: None,
+            response_raw: None,
         };
 
         Ok(result)
     }
-}
\ No newline at end of file
+
+    /// Socket.IO (Engine.IO v4): namespace connect, emit events, collect events until timeout
+    async fn socketio_request_response(&self, target_url: &str, options: &SocketIoOptions, headers: Option<Value>, credential: &Option<ApiCredential>) -> Result<FetchResult, String> {
+        let url = socketio_url(target_url, &options.path)?;
+        let namespace = options.namespace.clone().unwrap_or("/".to_string());
+
+        // Connect
+        let started = Instant::now();
+        let deadline = time::Instant::now() + self.timeout_duration;
+        let (ws_stream, status_code, server_headers) = connect(&url, headers, credential, deadline).await?;
+        let connected = started.elapsed();
+        let (mut write, mut read) = ws_stream.split();
+
+        let mut collected_messages: Vec<Value> = Vec::new();
+        let mut close: Option<Value> = None;
+        let mut error: Option<String> = None;
+        let mut sid: Option<String> = None;
+        let mut first_message: Option<Duration> = None;
+        let sleep_timer = sleep(self.timeout_duration);
+        tokio::pin!(sleep_timer);
+
+        let stop_reason = loop {
+            let text = tokio::select! {
+                msg = read.next() => {
+                    match msg {
+                        Some(Ok(Message::Text(text))) => text.to_string(),
+                        Some(Ok(Message::Close(frame))) => {
+                            close = Some(close_to_json(frame));
+                            continue;
+                        }
+                        Some(Ok(_)) => continue,
+                        Some(Err(e)) => return Err(format!("[SIO] Error: {}", e)),
+                        None => break if close.is_some() { "close" } else { "eof" },
+                    }
+                }
+                _ = &mut sleep_timer => {
+                    debug!("[SIO] Timeout reached, stopping listener.");
+                    break "timeout";
+                }
+            };
+
+            match parse_packet(&text) {
+                Packet::Open(handshake) => {
+                    debug!("[SIO] Engine.IO open, connecting namespace {}", namespace);
+                    sid = handshake["sid"].as_str().map(String::from);
+                    send_text(&mut write, connect_packet(&namespace, &options.auth)).await?;
+                }
+                Packet::Ping => send_text(&mut write, "3".to_string()).await?,
+                Packet::Connect { namespace: nsp, .. } if nsp == namespace => {
+                    debug!("[SIO] Namespace {} connected, emit {} event", namespace, options.emit.len());
+                    for emit in &options.emit {
+                        send_text(&mut write, event_packet(&namespace, &emit.event, &emit.args)).await?;
+                    }
+                }
+                Packet::ConnectError { data, .. } => {
+                    error = Some(format!("Socket.IO connect error: {}", data));
+                    break "connect_error";
+                }
+                Packet::Disconnect { namespace: nsp } if nsp == namespace => break "disconnect",
+                Packet::Close => break "close",
+                Packet::Event { namespace: nsp, event, args, .. }
+                    if nsp == namespace && (options.events.is_empty() || options.events.contains(&event)) => {
+                    first_message.get_or_insert_with(|| started.elapsed());
+                    collected_messages.push(json!({
+                        "received_at": Utc::now(),
+                        "event": event,
+                        "args": args,
+                    }));
+                }
+                _ => {}
+            }
+        };
+
+        if stop_reason == "timeout" {
+            let _ = send_text(&mut write, disconnect_packet(&namespace)).await;
+            let _ = write.send(Message::Close(None)).await;
+        }
+        if sid.is_none() && error.is_none() {
+            error = Some("Socket.IO handshake not received".to_string());
+        }
+
+        let response = json!({
+            "mode": "socketio",
+            "sid": sid,
+            "namespace": namespace,
+            "messages": collected_messages,
+            "close": close,
+            "stop_reason": stop_reason,
+        }).to_string();
+
+        Ok(FetchResult {
+            status_code,
+            headers: json!(server_headers),
+            response,
+            error,
+            meta: Some(json!({ "timing": stream_timing(connected, first_message, started.elapsed()) })),
+            content_type: None,
+            response_raw: None,
+        })
+    }
+}
+
+/// Open websocket with headers (and mTLS credential) before `deadline`, return stream, status & server headers
+async fn connect(target_url: &str, headers: Option<Value>, credential: &Option<ApiCredential>, deadline: time::Instant) -> Result<(WebSocketStream<MaybeTlsStream<TcpStream>>, i16, HashMap<String, String>), String> {
+    let mut request = target_url
+        .into_client_request()
+        .map_err(|e| format!("Invalid URL or Request: {}", e))?;
+
+    let header_map = json_to_headermap(headers).await;
+    request.headers_mut().extend(header_map);
+
+    let connector = match credential {
+        Some(credential) => Some(Connector::Rustls(Arc::new(client_config(credential)?))),
+        None => None,
+    };
+
+    let (ws_stream, response) = timeout_at(deadline, connect_async_tls_with_config(request, None, false, connector))
+        .await
+        .map_err(|_| "Failed connect to websocket: timeout".to_string())?
+        .map_err(|e| format!("Failed connect to websocket: {}", e))?;
+
+    let status_code = response.status().as_u16() as i16;
+    let mut server_headers = HashMap::new();
+    for (key, value) in response.headers() {
+        let val_str = value.to_str().unwrap_or("").to_string();
+        server_headers.insert(key.to_string(), val_str);
+    }
+
+    Ok((ws_stream, status_code, server_headers))
+}
+
+async fn send_text<S>(write: &mut S, text: String) -> Result<(), String>
+where
+    S: SinkExt<Message> + Unpin,
+    S::Error: std::fmt::Display,
+{
+    write
+        .send(Message::Text(text.into()))
+        .await
+        .map_err(|e| format!("Failed send message: {}", e))
+}
+/// Message with receive time, opcode and payload (binary as base64)
+fn message_to_json(message: Message) -> Value {
+    let (opcode, data, encoding) = match message {
+        Message::Text(text) => ("text", text.to_string(), "text"),
+        Message::Binary(data) => ("binary", STANDARD.encode(&data), "base64"),
+        Message::Ping(data) => ("ping", STANDARD.encode(&data), "base64"),
+        Message::Pong(data) => ("pong", STANDARD.encode(&data), "base64"),
+        Message::Close(_) | Message::Frame(_) => ("close", String::new(), "text"),
+    };
+
+    json!({
+        "received_at": Utc::now(),
+        "opcode": opcode,
+        "encoding": encoding,
+        "data": data,
+    })
+}
+
+/// `{connect_ms, first_message_ms, total_ms}` from run start, connect include handshake (DNS, TCP, TLS, upgrade)
+fn stream_timing(connect: Duration, first_message: Option<Duration>, total: Duration) -> Value {
+    json!({
+        "connect_ms": timing::ms(connect),
+        "first_message_ms": first_message.map(timing::ms),
+        "total_ms": timing::ms(total),
+    })
+}
+
+fn close_to_json(frame: Option<CloseFrame>) -> Value {
+    match frame {
+        Some(frame) => json!({ "code": u16::from(frame.code), "reason": frame.reason.to_string() }),
+        None => json!({ "code": null, "reason": "" }),
+    }
+}
+
+/// Check text message against expect step, store `save` values when matched
+fn match_message(text: &str, pattern: &Option<Regex>, path: &Option<String>, equals: &Option<Value>, save: &HashMap<String, String>, saved: &mut HashMap<String, String>) -> bool {
+    if let Some(pattern) = pattern && !pattern.is_match(text) {
+        return false;
+    }
+
+    let parsed: Option<Value> = serde_json::from_str(text).ok();
+    if let Some(path) = path {
+        let Some(selected) = parsed.as_ref().and_then(|v| json_path::select(v, path)) else {
+            return false;
+        };
+        if let Some(equals) = equals && selected != equals {
+            return false;
+        }
+    }
+
+    for (name, path) in save {
+        if let Some(value) = parsed.as_ref().and_then(|v| json_path::select(v, path)) {
+            saved.insert(name.clone(), json_path::value_to_string(value));
+        }
+    }
+
+    true
+}
+
+/// Replace `{{name}}` with saved value
+fn render_template(message: &str, saved: &HashMap<String, String>) -> String {
+    saved.iter().fold(message.to_string(), |acc, (name, value)| {
+        acc.replace(&format!("{{{{{}}}}}", name), value)
+    })
+}
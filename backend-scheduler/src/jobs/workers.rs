@@ -1,8 +1,13 @@
 use apalis::prelude::*;
 use apalis_sql::context::SqlContext;
-use crate::jobs::rest;
-use crate::models::fetch::{ApiType, FetchResult};
-use crate::{models::fetch::{Api, CreateApiData}, repository::fetch::{FetchDataRepository, FetchExecuteRepository, FetchHeaderRepository, FetchRepository}, services::fetch::FetchService, state::AppState};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use crate::jobs::{assertion, graphql, rest, retry, sql, timing};
+use chrono::{DateTime, Utc};
+use serde_json::{Value, json};
+use crate::models::fetch::{ApiType, Assertions, RetryOn, RetryPolicy};
+use crate::utils::schedule;
+use crate::{models::fetch::{Api, ApiExecute, CreateApiData, CreateApiRun, FetchResult, RunOutcome}, repository::fetch::{FetchCredentialRepository, FetchDataRepository, FetchExecuteRepository, FetchHeaderRepository, FetchRepository, FetchRunRepository}, services::fetch::FetchService, state::AppState};
 
 pub async fn setup_background_workers(state: AppState,) {
     let concurrency = state.app_config.concurrency.clone();
@@ -21,16 +26,86 @@ pub async fn setup_background_workers(state: AppState,) {
     });
 }
 
-async fn worker_jobs(job: Api, mut ctx: SqlContext, state: Data<AppState>) -> Result<(), anyhow::Error> {
-    ctx.set_max_attempts(10);
+async fn worker_jobs(job: Api, task_id: TaskId, attempt: Attempt, ctx: SqlContext, state: Data<AppState>) -> Result<(), Error> {
+    // Abort (no more attempt) passed to apalis as is, other error retried up to max_attempts
+    run_job(job, task_id, attempt, ctx, state).await.map_err(|e| match e.downcast::<Error>() {
+        Ok(e) => e,
+        Err(e) => Error::Failed(Arc::new(e.into())),
+    })
+}
 
+async fn run_job(job: Api, task: TaskId, attempt: Attempt, ctx: SqlContext, state: Data<AppState>) -> Result<(), anyhow::Error> {
     // Service data
     let execute_repo = FetchExecuteRepository::new(state.database.clone());
     let fetch_repo = FetchRepository::new(state.database.clone());
     let header_repo = FetchHeaderRepository::new(state.database.clone());
+    let credential_repo = FetchCredentialRepository::new(state.database.clone());
     let data_repo = FetchDataRepository::new(state.database.clone());
+    let run_repo = FetchRunRepository::new(state.database.clone());
     let fetch_service = FetchService::new((*state).clone());
     let fetch_api = fetch_repo.get_by_id(&job.id).await?;
+
+    // Run history entry, outcome set when recorded
+    let task_id = task.to_string();
+    let attempt = attempt.current() as u32;
+    let run = CreateApiRun {
+        fetch_id: fetch_api.id,
+        job_id: Some(task_id.clone()),
+        planned_at: if job.run_now { None } else { fetch_api.scheduled_at },
+        started_at: Utc::now(),
+        attempt: attempt as i32,
+        outcome: RunOutcome::Success,
+        error: None,
+        data_id: None,
+    };
+
+    // Paused fetch: no run, no next job (extra run still allowed)
+    if !fetch_api.is_active && !job.run_now {
+        tracing::info!("Fetch {} is paused, job dropped", fetch_api.id);
+        record_run(&run_repo, CreateApiRun { outcome: RunOutcome::Skipped, error: Some("Fetch is paused".to_string()), ..run }).await;
+        return Ok(());
+    }
+
+    // Retry of a job replaced meanwhile (execute update), the new job own the schedule
+    if attempt > 1 && !job.run_now && fetch_api.job_id.as_deref().is_some_and(|job_id| job_id != task_id) {
+        tracing::info!("Job {} of fetch {} replaced, retry dropped", task_id, fetch_api.id);
+        record_run(&run_repo, CreateApiRun { outcome: RunOutcome::Skipped, error: Some("Job replaced".to_string()), ..run }).await;
+        return Ok(());
+    }
+
+    // Misfire: next slots passed while job waited (worker down / delayed), retry keep its slot
+    let execute = execute_repo.find_by_id(fetch_api.execute_id).await?;
+    let schedule_run = execute.is_repeat && !job.run_now;
+    let mut previous = None;
+    if schedule_run {
+        let holidays = fetch_service.holidays(&execute)
+            .await.map_err(|e| anyhow::anyhow!("Failed to load holidays: {:?}", e))?;
+        let planned = fetch_api.scheduled_at.unwrap_or_else(Utc::now);
+        let misfire = schedule::attempt_misfire(&execute, &holidays, attempt, planned, Utc::now()).map_err(|e| anyhow::anyhow!(e))?;
+        if misfire.previous != planned {
+            fetch_repo.update_scheduled_at(fetch_api.id, &task_id, misfire.previous).await?;
+        }
+        if misfire.missed > 0 {
+            tracing::warn!("Fetch {} misfire ({:?}), {} slot(s) missed since {}", fetch_api.id, execute.misfire_policy, misfire.missed, planned);
+            fetch_repo.add_missed_count(fetch_api.id, misfire.missed).await?;
+            let error = format!("Misfire ({:?}): {} slot(s) missed since {}", execute.misfire_policy, misfire.missed, planned);
+            record_run(&run_repo, CreateApiRun { outcome: RunOutcome::Skipped, error: Some(error), ..run.clone() }).await;
+        }
+        previous = Some(misfire.previous);
+        if !misfire.run {
+            return reschedule(&fetch_service, &fetch_repo, fetch_api.id, &task_id, execute, previous).await;
+        }
+    }
+    let mut policy = RetryPolicy::resolve(&fetch_api.retry, &execute.retry).unwrap_or_else(|e| {
+        tracing::warn!("Fetch {} retry policy ignored: {}", fetch_api.id, e);
+        RetryPolicy::default()
+    });
+    // Limit set on the job when pushed, policy changed later only lower it
+    policy.max_attempts = policy.max_attempts.min(ctx.max_attempts().max(1) as u32);
+    let assertions = Assertions::parse(&fetch_api.assertions).unwrap_or_else(|e| {
+        tracing::warn!("Fetch {} assertions ignored: {}", fetch_api.id, e);
+        None
+    });
     let headers_json = if let Some(h_id) = fetch_api.header_id {
         match header_repo.find_by_id(h_id).await {
             Ok(data) => Some(data.headers),
@@ -43,25 +118,112 @@ async fn worker_jobs(job: Api, mut ctx: SqlContext, state: Data<AppState>) -> Re
         None
     };
 
+    let credential = match fetch_api.credential_id {
+        Some(c_id) => match credential_repo.find_by_id(c_id).await {
+            Ok(data) => Some(data),
+            Err(e) => {
+                tracing::warn!("Credential ID {} not found: {:?}. Default.", c_id, e);
+                None
+            }
+        },
+        None => None,
+    };
+
+    let started = Instant::now();
     let response = match fetch_api.r#type {
-        ApiType::Rest => rest::request_response(state.http_client.clone(), &fetch_api.endpoint, &fetch_api.method, &fetch_api.payload, headers_json).await,
-        ApiType::Websocket => state.ws_client.request_response(&fetch_api.endpoint, &fetch_api.payload, headers_json).await,
-        
+        ApiType::Rest => rest::request_response(&state.http_client, &fetch_api.endpoint, &fetch_api.method, &fetch_api.query, &fetch_api.body_mode, &fetch_api.payload, &fetch_api.options, headers_json, &credential).await,
+        ApiType::Websocket => state.ws_client.request_response(&fetch_api.endpoint, &fetch_api.payload, &fetch_api.options, headers_json, &credential).await,
+        ApiType::Mqtt => state.mqtt_client.request_response(&fetch_api.endpoint, &fetch_api.payload, &fetch_api.topic).await,
+        ApiType::Graphql => graphql::request_response(&state.http_client, &fetch_api.endpoint, &fetch_api.payload, &fetch_api.options, headers_json, &credential).await,
+        ApiType::Sse => match state.sse_client.request_response(&state.http_client, &fetch_api.endpoint, &fetch_api.method, &fetch_api.payload, &fetch_api.options, headers_json, &credential, &fetch_api.last_event_id).await {
+            Ok(sse) => {
+                if sse.last_event_id != fetch_api.last_event_id {
+                    fetch_repo.update_last_event_id(fetch_api.id, sse.last_event_id).await?;
+                }
+                Ok(sse.result)
+            }
+            Err(e) => Err(e),
+        },
+        ApiType::Tcp => state.socket_client.tcp_request_response(&fetch_api.endpoint, &fetch_api.payload, &fetch_api.options).await,
+        ApiType::Udp => state.socket_client.udp_request_response(&fetch_api.endpoint, &fetch_api.payload, &fetch_api.options).await,
+        ApiType::Tls => state.tls_client.request_response(&fetch_api.endpoint, &fetch_api.options).await,
+        ApiType::Dns => state.dns_client.request_response(&fetch_api.endpoint, &fetch_api.options).await,
+        ApiType::Sql => sql::request_response(&fetch_api.payload, &fetch_api.options, &credential).await,
+    };
+
+    let elapsed = started.elapsed();
+    let latency_ms = elapsed.as_millis() as u64;
+    let response = response.map(|result| with_timing(result, elapsed));
+
+    // Assertion report stored with data
+    let report = match (&assertions, &response) {
+        (Some(assertions), Ok(result)) => Some(assertion::evaluate(assertions, result, latency_ms)),
+        _ => None,
     };
 
-    // Save data
-    let result = match response {
-        Ok(result) => FetchResult { status_code: result.status_code, headers: result.headers, response: result.response },
-        Err(msg) => return Err(anyhow::anyhow!(msg)),
+    // Failed run: outcome, message, Retry-After. 5xx / 429 not in retry_on stored as normal response
+    let failure = match &response {
+        Ok(result) => retry::failure(result)
+            .filter(|(outcome, _)| *outcome == RetryOn::Network || policy.retry_on.contains(outcome))
+            .or_else(|| report.as_ref().and_then(assertion::failure_message).map(|msg| (RetryOn::Assertion, msg)))
+            .map(|(outcome, msg)| (outcome, msg, retry::retry_after(&result.headers, Utc::now()))),
+        Err(msg) => Some((RetryOn::Network, msg.clone(), None)),
     };
+    let data_id = match response {
+        Ok(result) => Some(save_result(&data_repo, &fetch_api, result, report).await?),
+        Err(_) => None,
+    };
+    let (outcome, error) = match &failure {
+        Some((_, msg, _)) if is_timeout(msg) => (RunOutcome::Timeout, Some(msg.clone())),
+        Some((_, msg, _)) => (RunOutcome::Failed, Some(msg.clone())),
+        None => (RunOutcome::Success, None),
+    };
+    record_run(&run_repo, CreateApiRun { outcome, error, data_id, ..run }).await;
+
+    // Retry same job later, apalis pick failed job again after run_at
+    if let Some((outcome, msg, retry_after)) = &failure
+        && policy.retry_on.contains(outcome)
+        && attempt < policy.max_attempts {
+        let delay = retry::retry_delay(&policy, attempt, *retry_after, rand::random());
+        tracing::warn!("Fetch {} attempt {}/{} failed ({:?}), retry in {}s: {}", fetch_api.id, attempt, policy.max_attempts, outcome, delay.num_seconds(), msg);
+        let mut retry_job = Request::new_with_ctx(job, SqlContext::new());
+        retry_job.parts.task_id = task;
+        state.job_queue.clone().reschedule(retry_job, delay.to_std()?).await?;
+        return Err(anyhow::anyhow!(msg.clone()));
+    }
 
-    let fetch_id = fetch_api.id.clone();
+    // Run done (succeeded or gave up), extra run not counted in schedule
+    if !job.run_now {
+        fetch_repo.increment_run_count(fetch_api.id).await?;
+    }
+    if schedule_run {
+        reschedule(&fetch_service, &fetch_repo, fetch_api.id, &task_id, execute, previous).await?;
+    }
+    if let Some((_, msg, _)) = failure {
+        // No more apalis retry
+        return Err(Error::Abort(Arc::new(msg.into())).into());
+    }
+
+    Ok(())
+}
+
+/// Store response of done request, returns data id
+async fn save_result(data_repo: &FetchDataRepository, fetch_api: &Api, result: FetchResult, report: Option<Value>) -> Result<i32, anyhow::Error> {
+    let fetch_id = fetch_api.id;
     let fetch_job_id = fetch_api.job_id.clone().unwrap_or_else(|| "unknown".to_string());
     let name_data = format!("{} [{}-{}]", fetch_api.name,fetch_id, fetch_job_id);
 
     match fetch_api.r#type {
         ApiType::Rest => tracing::info!("[HTTP] Done request to {}. [{}]", &fetch_api.endpoint, result.status_code,),
         ApiType::Websocket => tracing::info!("[WS] Done request to {}. [{}]", &fetch_api.endpoint, result.status_code,),
+        ApiType::Mqtt => tracing::info!("[MQTT] Done request to {}. [{}]", &fetch_api.endpoint, result.status_code,),
+        ApiType::Graphql => tracing::info!("[GRAPHQL] Done request to {}. [{}]", &fetch_api.endpoint, result.status_code,),
+        ApiType::Sse => tracing::info!("[SSE] Done request to {}. [{}]", &fetch_api.endpoint, result.status_code,),
+        ApiType::Tcp => tracing::info!("[TCP] Done request to {}. [{}]", &fetch_api.endpoint, result.status_code,),
+        ApiType::Udp => tracing::info!("[UDP] Done request to {}. [{}]", &fetch_api.endpoint, result.status_code,),
+        ApiType::Tls => tracing::info!("[TLS] Done request to {}. [{}]", &fetch_api.endpoint, result.status_code,),
+        ApiType::Dns => tracing::info!("[DNS] Done request to {}. [{}]", &fetch_api.endpoint, result.status_code,),
+        ApiType::Sql => tracing::info!("[SQL] Done request to {}. [{}]", &fetch_api.endpoint, result.status_code,),
     }
     
     let response_data = CreateApiData {
@@ -70,18 +232,64 @@ async fn worker_jobs(job: Api, mut ctx: SqlContext, state: Data<AppState>) -> Re
         status_code: Some(result.status_code),
         response: Some(result.response),
         response_headers: Some(result.headers),
+        meta: result.meta,
+        content_type: result.content_type,
+        response_raw: result.response_raw,
+        assertions: report,
     };
 
-    data_repo.create(response_data).await?;
+    let data = data_repo.create(response_data).await?;
 
-    // Create repeatable jobs
-    let execute = execute_repo.find_by_id(fetch_api.execute_id).await?;
-    if execute.is_repeat {
-        let job_id = fetch_service.create_apalis_job(&fetch_api, execute)
-            .await.map_err(|e| anyhow::anyhow!("Failed to create repeatable jobs: {:?}", e))?;
-        let _ = fetch_repo.update_job_id(fetch_api.id, job_id)
-            .await.map_err(|e| anyhow::anyhow!("Failed to update job id: {:?}", e))?;
+    Ok(data.id)
+}
+
+/// Every stored run carry `meta.timing`, only total for clients without phase timing
+fn with_timing(mut result: FetchResult, elapsed: Duration) -> FetchResult {
+    if let Some(meta) = result.meta.get_or_insert_with(|| json!({})).as_object_mut()
+        && !meta.contains_key("timing") {
+        meta.insert("timing".to_string(), json!({ "total_ms": timing::ms(elapsed) }));
     }
 
+    result
+}
+
+/// Save run history, failure only logged
+async fn record_run(run_repo: &FetchRunRepository, run: CreateApiRun) {
+    let fetch_id = run.fetch_id;
+    if let Err(e) = run_repo.create(run).await {
+        tracing::warn!("Failed to record run of fetch {}: {:?}", fetch_id, e);
+    }
+}
+
+/// Timeout error of the clients (reqwest, tokio, socket)
+fn is_timeout(msg: &str) -> bool {
+    let msg = msg.to_ascii_lowercase();
+    msg.contains("timed out") || msg.contains("timeout")
+}
+
+/// Next job of repeat execute, after `previous` planned time.
+/// Fetch re-read under row lock, paused, deleted or job replaced (execute update) while running get no next job
+async fn reschedule(fetch_service: &FetchService, fetch_repo: &FetchRepository, fetch_id: i32, task_id: &str, execute: ApiExecute, previous: Option<DateTime<Utc>>) -> Result<(), anyhow::Error> {
+    let mut tx = fetch_repo.begin().await?;
+    let Some(fetch_api) = fetch_repo.lock_by_id(&mut tx, fetch_id).await? else {
+        tracing::info!("Fetch {} deleted, no next job", fetch_id);
+        return Ok(());
+    };
+    if !fetch_api.is_active {
+        tracing::info!("Fetch {} paused, no next job", fetch_id);
+        return Ok(());
+    }
+    if fetch_api.job_id.as_deref().is_some_and(|job_id| job_id != task_id) {
+        tracing::info!("Job {} of fetch {} replaced, no next job", task_id, fetch_id);
+        return Ok(());
+    }
+
+    let (job_id, scheduled_at) = fetch_service.create_apalis_job(&fetch_api, execute, previous)
+        .await.map_err(|e| anyhow::anyhow!("Failed to create repeatable jobs: {:?}", e))?
+        .unzip();
+    let _ = fetch_repo.update_job_id_tx(&mut tx, fetch_id, job_id, scheduled_at)
+        .await.map_err(|e| anyhow::anyhow!("Failed to update job id: {:?}", e))?;
+    tx.commit().await?;
+
     Ok(())
 }
\ No newline at end of file
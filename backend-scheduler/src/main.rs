@@ -1,7 +1,7 @@
 use axum::serve;
 use dotenvy::dotenv;
 use scheduler::{
-    config::*, create_app, db::postgres::{self, create_root_user, migrate_app}, jobs::{cleaner::start_job_cleaner, websocket::{WsJobs}, workers::setup_background_workers}, models::fetch::Api, state::{AppConfig, AppState}
+    config::*, create_app, db::postgres::{self, create_root_user, migrate_app}, jobs::{cleaner::start_job_cleaner, dns::DnsJobs, http::HttpClients, mqtt::MqttJobs, socket::SocketJobs, sse::SseJobs, tls::TlsJobs, websocket::{WsJobs}, workers::setup_background_workers}, models::fetch::Api, state::{AppConfig, AppState}
 };
 use std::sync::Arc;
 use apalis_sql::postgres::PostgresStorage;
@@ -33,6 +33,7 @@ async fn main() {
         access_ttl: config.access_ttl as i64,
         refresh_ttl: config.refresh_ttl as i64,
         concurrency: config.concurrency,
+        max_body_bytes: config.max_body_bytes,
 
     };
 
@@ -44,23 +45,37 @@ async fn main() {
         PostgresStorage::<Api>::new_with_config(pool.clone(), apalis_config);
     
     // Http request
-    let http_client = reqwest::Client::builder()
-        .user_agent("Teknohole/1.0")
-        .timeout(std::time::Duration::from_secs(10)) 
-        .pool_idle_timeout(std::time::Duration::from_secs(90))
-        .pool_max_idle_per_host(10)
-        .build()
-        .unwrap();
+    let http_client = HttpClients::new(config.http_timeout, config.max_body_bytes);
 
     // Websocket request
     let ws_client = WsJobs::new(config.ws_timeout);
+
+    // Mqtt request
+    let mqtt_client = MqttJobs::new(config.mqtt_timeout);
+
+    // Server-sent events request
+    let sse_client = SseJobs::new(config.sse_timeout);
+
+    // Tcp/Udp probe
+    let socket_client = SocketJobs::new(config.socket_timeout);
+
+    // Tls inspection
+    let tls_client = TlsJobs::new(config.socket_timeout);
+
+    // Dns lookup
+    let dns_client = DnsJobs::new(config.socket_timeout);
     
     //  State
     let state = AppState {
         app_config: Arc::new(app_config),
         database: pool,
-        http_client: http_client,
-        ws_client: ws_client,
+        http_client,
+        ws_client,
+        mqtt_client,
+        sse_client,
+        socket_client,
+        tls_client,
+        dns_client,
         job_queue: scheduler_storage,
     };
 
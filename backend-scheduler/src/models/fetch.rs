@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sqlx::FromRow;
-use chrono::{DateTime,Utc};
+use chrono::{DateTime,NaiveDate,Utc};
 
 // Struct for table fetch_api
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
@@ -10,8 +10,14 @@ use chrono::{DateTime,Utc};
 pub enum ApiType {
     Rest,
     Websocket,
-    // Mqtt,
-    // Graphql,
+    Mqtt,
+    Graphql,
+    Sse,
+    Tcp,
+    Udp,
+    Tls,
+    Dns,
+    Sql,
 }
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "fetch_api_method", rename_all = "lowercase")]
@@ -22,6 +28,18 @@ pub enum ApiMethod {
     Put,
     Patch,
     Delete,
+    Head,
+    Options,
+}
+#[derive(Debug, Clone, Default, Serialize, Deserialize, sqlx::Type, PartialEq)]
+#[sqlx(type_name = "fetch_api_body_mode", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum BodyMode {
+    #[default]
+    Raw,
+    Json,
+    Form,
+    Multipart,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -35,10 +53,33 @@ pub struct Api {
     pub job_id: Option<String>,
     pub description: String,
     pub payload: Option<String>,
+    pub options: Option<Value>,
+    pub query: Option<Value>,
+    // Default for job payload queued before the field exist
+    #[serde(default)]
+    pub body_mode: BodyMode,
     pub execute_id: i32,
     pub header_id: Option<i32>,
+    pub credential_id: Option<i32>,
     pub is_active: bool,
+    pub last_event_id: Option<String>,
+    /// Retry policy, override execute retry
+    pub retry: Option<Value>,
+    /// Response checks deciding run success
+    pub assertions: Option<Value>,
+    /// Runs done with current execute
+    #[serde(default)]
+    pub run_count: i64,
+    /// Planned time of pending job, base of the next run (fixed rate)
+    pub scheduled_at: Option<DateTime<Utc>>,
+    /// Slots missed by downtime / delay (misfire policy)
+    #[serde(default)]
+    pub missed_count: i64,
     pub updated_at: DateTime<Utc>,
+    /// Job payload only: extra run from /run, schedule untouched
+    #[sqlx(skip)]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub run_now: bool,
 }
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct CreateApi {
@@ -49,9 +90,15 @@ pub struct CreateApi {
     pub topic: Option<Value>,
     pub description: String,
     pub payload: Option<String>,
+    pub options: Option<Value>,
+    pub query: Option<Value>,
+    pub body_mode: Option<BodyMode>,
     pub execute_id: i32,
     pub header_id: Option<i32>,
+    pub credential_id: Option<i32>,
     pub is_active: Option<bool>,
+    pub retry: Option<Value>,
+    pub assertions: Option<Value>,
 }
 #[derive(Deserialize)]
 pub struct ReqCreateApi {
@@ -62,9 +109,16 @@ pub struct ReqCreateApi {
     pub topic: Option<Value>,
     pub description: String,
     pub payload: Option<Value>,
+    pub options: Option<Value>,
+    /// Query params, value string or array of string
+    pub query: Option<Value>,
+    pub body_mode: Option<BodyMode>,
     pub execute_id: i32,
     pub header_id: Option<i32>,
+    pub credential_id: Option<i32>,
     pub is_active: Option<bool>,
+    pub retry: Option<Value>,
+    pub assertions: Option<Value>,
 }
 impl ReqCreateApi {
     pub fn into_model(self) -> CreateApi {
@@ -89,9 +143,15 @@ impl ReqCreateApi {
             topic: self.topic,
             description: self.description,
             payload: payload_string, 
+            options: self.options,
+            query: self.query,
+            body_mode: self.body_mode,
             execute_id: self.execute_id,
             header_id: self.header_id,
-            is_active: self.is_active
+            credential_id: self.credential_id,
+            is_active: self.is_active,
+            retry: self.retry,
+            assertions: self.assertions,
         }
     }
 }
@@ -105,9 +165,15 @@ pub struct UpdateApi {
     pub topic: Option<Value>,
     pub description: Option<String>,
     pub payload: Option<String>,
+    pub options: Option<Value>,
+    pub query: Option<Value>,
+    pub body_mode: Option<BodyMode>,
     pub execute_id: Option<i32>,
     pub header_id: Option<i32>,
+    pub credential_id: Option<i32>,
     pub is_active: Option<bool>,
+    pub retry: Option<Value>,
+    pub assertions: Option<Value>,
 }
 
 // Struct for table fetch_api_members
@@ -139,6 +205,20 @@ pub struct UpdateApiMembers {
     pub role: Role, 
 }
 
+/// Handling of run slots missed while worker down / delayed
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, sqlx::Type, PartialEq)]
+#[sqlx(type_name = "misfire_policy", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum MisfirePolicy {
+    /// Drop late run and missed slots, continue at next slot
+    Skip,
+    /// One run for all missed slots
+    #[default]
+    RunOnce,
+    /// Run every missed slot (latest `misfire_limit`) back to back
+    RunAll,
+}
+
 // Struct for table fetch_api_execute
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "execute_type", rename_all = "lowercase")]
@@ -148,6 +228,12 @@ pub enum ExecuteType {
     Minutes,
     Hours,
     Days,
+    /// Cron expression on `cron` field, evaluated in `timezone`
+    Cron,
+    /// RFC 5545 recurrence on `rrule` field (DTSTART, RRULE, EXDATE lines)
+    Rrule,
+    /// Sun / prayer time anchored, settings on `solar` field
+    Solar,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -158,8 +244,34 @@ pub struct ApiExecute {
     pub is_repeat: bool,
     pub r#type: Option<ExecuteType>,
     pub value: i64,
+    pub cron: Option<String>,
+    pub rrule: Option<String>,
+    pub solar: Option<Value>,
+    /// IANA time zone (Asia/Jakarta), UTC when empty
+    pub timezone: Option<String>,
+    /// Holiday calendar, run on listed dates skipped
+    pub calendar_id: Option<i32>,
+    /// Absolute first run, one-shot when not repeat
+    pub run_at: Option<DateTime<Utc>>,
+    /// Repeat window and max run count
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub max_runs: Option<i64>,
+    pub misfire_policy: MisfirePolicy,
+    /// Max missed slots run by run_all
+    pub misfire_limit: Option<i32>,
+    /// Retry policy of fetches using this execute
+    pub retry: Option<Value>,
     pub updated_at: DateTime<Utc>,
 }
+/// Execute update result with the fetches rescheduled by it
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiExecuteCascade {
+    #[serde(flatten)]
+    pub execute: ApiExecute,
+    pub affected_fetches: Vec<i32>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct CreateApiExecute {
     pub user_id: i32,
@@ -167,6 +279,18 @@ pub struct CreateApiExecute {
     pub is_repeat: bool,
     pub r#type: Option<ExecuteType>,
     pub value: i64,
+    pub cron: Option<String>,
+    pub rrule: Option<String>,
+    pub solar: Option<Value>,
+    pub timezone: Option<String>,
+    pub calendar_id: Option<i32>,
+    pub run_at: Option<DateTime<Utc>>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub max_runs: Option<i64>,
+    pub misfire_policy: Option<MisfirePolicy>,
+    pub misfire_limit: Option<i32>,
+    pub retry: Option<Value>,
 }
 
 // DTO execute
@@ -175,7 +299,20 @@ pub struct ReqCreateApiExecute {
     pub name: String,
     pub is_repeat: bool,
     pub r#type: Option<ExecuteType>,
+    #[serde(default)]
     pub value: i64,
+    pub cron: Option<String>,
+    pub rrule: Option<String>,
+    pub solar: Option<Value>,
+    pub timezone: Option<String>,
+    pub calendar_id: Option<i32>,
+    pub run_at: Option<DateTime<Utc>>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub max_runs: Option<i64>,
+    pub misfire_policy: Option<MisfirePolicy>,
+    pub misfire_limit: Option<i32>,
+    pub retry: Option<Value>,
 }
 impl ReqCreateApiExecute {
     pub fn into_model(self, user_id:i32) -> CreateApiExecute {
@@ -185,6 +322,18 @@ impl ReqCreateApiExecute {
             is_repeat: self.is_repeat,
             r#type: self.r#type,
             value: self.value,
+            cron: self.cron,
+            rrule: self.rrule,
+            solar: self.solar,
+            timezone: self.timezone,
+            calendar_id: self.calendar_id,
+            run_at: self.run_at,
+            starts_at: self.starts_at,
+            ends_at: self.ends_at,
+            max_runs: self.max_runs,
+            misfire_policy: self.misfire_policy,
+            misfire_limit: self.misfire_limit,
+            retry: self.retry,
         }
     }
 }
@@ -195,6 +344,131 @@ pub struct UpdateApiExecute {
     pub is_repeat: Option<bool>,
     pub r#type: Option<ExecuteType>,
     pub value: Option<i64>,
+    pub cron: Option<String>,
+    pub rrule: Option<String>,
+    pub solar: Option<Value>,
+    pub timezone: Option<String>,
+    pub calendar_id: Option<i32>,
+    pub run_at: Option<DateTime<Utc>>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub max_runs: Option<i64>,
+    pub misfire_policy: Option<MisfirePolicy>,
+    pub misfire_limit: Option<i32>,
+    pub retry: Option<Value>,
+}
+
+// Event anchor of solar execute
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SolarEvent {
+    /// Fajr - 10 minutes
+    Imsak,
+    Fajr,
+    Sunrise,
+    Dhuhr,
+    Asr,
+    Sunset,
+    Maghrib,
+    Isha,
+}
+
+/// Prayer time calculation method (fajr / isha sun angle)
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PrayerMethod {
+    /// Kementerian Agama RI: 20° / 18°
+    #[default]
+    Kemenag,
+    /// Muslim World League: 18° / 17°
+    Mwl,
+    /// Islamic Society of North America: 15° / 15°
+    Isna,
+    /// Egyptian General Authority of Survey: 19.5° / 17.5°
+    Egypt,
+    /// Umm al-Qura Makkah: 18.5° / 90 minutes after maghrib
+    Makkah,
+    /// University of Islamic Sciences Karachi: 18° / 18°
+    Karachi,
+    /// JAKIM Malaysia: 20° / 18°
+    Jakim,
+    /// MUIS Singapore: 20° / 18°
+    Singapore,
+    /// Institute of Geophysics Tehran: 17.7° / 14°, maghrib 4.5°
+    Tehran,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AsrMethod {
+    /// Shadow length 1x (Shafi'i, Maliki, Hanbali)
+    #[default]
+    Standard,
+    /// Shadow length 2x
+    Hanafi,
+}
+
+// Settings of solar execute (fetch_api_execute.solar)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SolarOptions {
+    pub event: SolarEvent,
+    pub latitude: f64,
+    pub longitude: f64,
+    #[serde(default)]
+    pub method: PrayerMethod,
+    #[serde(default)]
+    pub asr: AsrMethod,
+    /// Minutes from event, negative is before (-10 = 10 minutes before)
+    #[serde(default)]
+    pub offset_minutes: i64,
+}
+impl SolarOptions {
+    pub fn parse(options: &Option<Value>) -> Result<Self, String> {
+        let val = match options {
+            Some(val) if !val.is_null() => val.clone(),
+            _ => return Err("Solar settings are required for solar execute".to_string()),
+        };
+        let parsed: SolarOptions = serde_json::from_value(val).map_err(|e| format!("Invalid solar settings: {}", e))?;
+        if !(-90.0..=90.0).contains(&parsed.latitude) || !(-180.0..=180.0).contains(&parsed.longitude) {
+            return Err("Invalid solar settings: latitude/longitude out of range".to_string());
+        }
+        if parsed.offset_minutes.abs() > 720 {
+            return Err("Invalid solar settings: offset_minutes must be within 12 hours".to_string());
+        }
+
+        Ok(parsed)
+    }
+}
+
+// Struct for table fetch_api_calendar (holiday dates)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ApiCalendar {
+    pub id: i32,
+    pub user_id: i32,
+    pub name: String,
+    pub dates: Vec<NaiveDate>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CreateApiCalendar {
+    pub user_id: i32,
+    pub name: String,
+    pub dates: Vec<NaiveDate>,
+}
+
+// DTO calendar, .ics text content
+#[derive(Deserialize)]
+pub struct ReqCreateApiCalendar {
+    pub name: String,
+    pub ics: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateApiCalendar {
+    pub name: Option<String>,
+    /// New .ics replace all dates
+    pub ics: Option<String>,
 }
 
 // Struct for table fetch_api_header
@@ -237,6 +511,91 @@ pub struct UpdateApiHeader {
     pub headers: Option<Value>,
 }
 
+// Struct for table fetch_api_credential
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ApiCredential {
+    pub id: i32,
+    pub user_id: i32,
+    pub name: String,
+    pub client_cert: Option<String>,
+    #[serde(skip_serializing)]
+    pub client_key: Option<String>,
+    pub ca_bundle: Option<String>,
+    /// Postgres connection string for sql fetch (password inside)
+    #[serde(skip_serializing)]
+    pub database_url: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CreateApiCredential {
+    pub user_id: i32,
+    pub name: String,
+    pub client_cert: Option<String>,
+    pub client_key: Option<String>,
+    pub ca_bundle: Option<String>,
+    pub database_url: Option<String>,
+}
+
+// DTO credential, PEM text
+#[derive(Deserialize)]
+pub struct ReqCreateApiCredential {
+    pub name: String,
+    pub client_cert: Option<String>,
+    pub client_key: Option<String>,
+    pub ca_bundle: Option<String>,
+    pub database_url: Option<String>,
+}
+
+impl ReqCreateApiCredential {
+    pub fn into_model(self, user_id: i32) -> CreateApiCredential {
+        CreateApiCredential {
+            user_id,
+            name: self.name,
+            client_cert: self.client_cert,
+            client_key: self.client_key,
+            ca_bundle: self.ca_bundle,
+            database_url: self.database_url,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UpdateApiCredential {
+    pub name: Option<String>,
+    pub client_cert: Option<String>,
+    pub client_key: Option<String>,
+    pub ca_bundle: Option<String>,
+    pub database_url: Option<String>,
+}
+
+// Credential response, private key never returned
+#[derive(Debug, Serialize)]
+pub struct ApiCredentialResponse {
+    pub id: i32,
+    pub user_id: i32,
+    pub name: String,
+    pub client_cert: Option<String>,
+    pub has_client_key: bool,
+    pub ca_bundle: Option<String>,
+    pub has_database_url: bool,
+    pub updated_at: DateTime<Utc>,
+}
+impl From<ApiCredential> for ApiCredentialResponse {
+    fn from(data: ApiCredential) -> Self {
+        ApiCredentialResponse {
+            id: data.id,
+            user_id: data.user_id,
+            name: data.name,
+            client_cert: data.client_cert,
+            has_client_key: data.client_key.is_some(),
+            ca_bundle: data.ca_bundle,
+            has_database_url: data.database_url.is_some(),
+            updated_at: data.updated_at,
+        }
+    }
+}
+
 // Struct for table fetch_api_data
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct ApiData {
@@ -246,6 +605,10 @@ pub struct ApiData {
     pub status_code: i16,
     pub response: String,
     pub response_headers: Value,
+    pub meta: Option<Value>,
+    pub content_type: Option<String>,
+    /// Assertion report `{passed, results}`
+    pub assertions: Option<Value>,
     pub updated_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
 }
@@ -258,15 +621,67 @@ pub struct ApiDataResponse {
     pub status_code: i16,
     pub response: Value, 
     pub response_headers: Value,
+    pub meta: Option<Value>,
+    pub content_type: Option<String>,
+    /// Assertion report `{passed, results}`
+    pub assertions: Option<Value>,
     pub updated_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
 }
+/// Result of one job run
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq)]
+#[sqlx(type_name = "run_outcome", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum RunOutcome {
+    Success,
+    Failed,
+    Timeout,
+    /// Not run (paused, misfire)
+    Skipped,
+}
+
+// Struct for table fetch_api_runs (run history)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ApiRun {
+    pub id: i32,
+    pub fetch_id: i32,
+    pub job_id: Option<String>,
+    /// Schedule slot of the job, null for extra run
+    pub planned_at: Option<DateTime<Utc>>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub attempt: i32,
+    pub outcome: RunOutcome,
+    pub error: Option<String>,
+    /// Stored response (fetch_api_data)
+    pub data_id: Option<i32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateApiRun {
+    pub fetch_id: i32,
+    pub job_id: Option<String>,
+    pub planned_at: Option<DateTime<Utc>>,
+    pub started_at: DateTime<Utc>,
+    pub attempt: i32,
+    pub outcome: RunOutcome,
+    pub error: Option<String>,
+    pub data_id: Option<i32>,
+}
+
+// Original body for raw download
+#[derive(Debug, Clone, FromRow)]
+pub struct ApiDataRaw {
+    pub response: String,
+    pub response_raw: Option<Vec<u8>>,
+    pub content_type: Option<String>,
+}
 // Helper Text DB Model to json response 
 impl From<ApiData> for ApiDataResponse {
     fn from(data: ApiData) -> Self {
         let parsed_response: Value = serde_json::from_str(&data.response)
             .unwrap_or_else(|_| {
-                Value::String(data.response) 
+                Value::String(data.response.clone()) 
             });
 
         ApiDataResponse {
@@ -276,6 +691,9 @@ impl From<ApiData> for ApiDataResponse {
             status_code: data.status_code,
             response: parsed_response,
             response_headers: data.response_headers,
+            meta: data.meta,
+            content_type: data.content_type,
+            assertions: data.assertions,
             updated_at: data.updated_at,
             created_at: data.created_at,
         }
@@ -289,6 +707,10 @@ pub struct CreateApiData {
     pub status_code: Option<i16>,
     pub response: Option<String>,
     pub response_headers: Option<Value>,
+    pub meta: Option<Value>,
+    pub content_type: Option<String>,
+    pub response_raw: Option<Vec<u8>>,
+    pub assertions: Option<Value>,
 }
 // DTO payload data
 #[derive(Deserialize)]
@@ -307,6 +729,10 @@ impl ReqCreateApiData {
             status_code: self.status_code,
             response: self.response,
             response_headers: self.response_headers,
+            meta: None,
+            content_type: None,
+            response_raw: None,
+            assertions: None,
         }
     }
 }
@@ -324,4 +750,654 @@ pub struct FetchResult {
     pub status_code: i16,
     pub headers: Value,
     pub response: String,
-}
\ No newline at end of file
+    /// Response stored but the run counts as failed
+    pub error: Option<String>,
+    /// Run metadata stored in fetch_api_data.meta
+    pub meta: Option<Value>,
+    pub content_type: Option<String>,
+    /// Original bytes when body is binary or not utf-8
+    pub response_raw: Option<Vec<u8>>,
+}
+
+// Topic config for mqtt fetch (fetch_api.topic)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MqttTopic {
+    #[serde(default)]
+    pub publish: Vec<MqttTopicItem>,
+    #[serde(default)]
+    pub subscribe: Vec<MqttTopicItem>,
+    /// Listen window in seconds, default MQTT_TIMEOUT
+    pub listen: Option<u64>,
+}
+
+impl MqttTopic {
+    pub fn parse(topic: &Option<Value>) -> Result<Self, String> {
+        let parsed: MqttTopic = match topic {
+            Some(val) if !val.is_null() => serde_json::from_value(val.clone())
+                .map_err(|e| format!("Invalid MQTT topic config: {}", e))?,
+            _ => MqttTopic::default(),
+        };
+        if parsed.publish.is_empty() && parsed.subscribe.is_empty() {
+            return Err("No MQTT topic to publish or subscribe".to_string());
+        }
+
+        for item in parsed.publish.iter().chain(&parsed.subscribe) {
+            if item.topic.is_empty() {
+                return Err("Invalid MQTT topic config: topic must not be empty".to_string());
+            }
+            if item.qos > 2 {
+                return Err(format!("Invalid MQTT QoS: {}", item.qos));
+            }
+        }
+        // Wildcards only valid in subscribe filter
+        if let Some(item) = parsed.publish.iter().find(|item| item.topic.contains(['+', '#'])) {
+            return Err(format!("Invalid MQTT publish topic '{}': wildcard not allowed", item.topic));
+        }
+
+        Ok(parsed)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MqttTopicItem {
+    pub topic: String,
+    #[serde(default)]
+    pub qos: u8,
+    #[serde(default)]
+    pub retain: bool,
+}
+
+// Payload for graphql fetch (fetch_api.payload)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphqlPayload {
+    pub query: String,
+    #[serde(default, alias = "operationName")]
+    pub operation_name: Option<String>,
+    #[serde(default)]
+    pub variables: Option<Value>,
+}
+impl GraphqlPayload {
+    /// JSON object {query, operation_name, variables} or a raw query document
+    pub fn parse(payload: &Option<String>) -> Result<Self, String> {
+        let payload = payload.as_deref().unwrap_or("").trim();
+        if payload.is_empty() {
+            return Err("GraphQL query is required".to_string());
+        }
+
+        if payload.starts_with('{') && let Ok(val) = serde_json::from_str::<Value>(payload) && val.is_object() {
+            let parsed: GraphqlPayload = serde_json::from_value(val)
+                .map_err(|e| format!("Invalid GraphQL payload: {}", e))?;
+            if parsed.query.trim().is_empty() {
+                return Err("GraphQL query is required".to_string());
+            }
+            if let Some(vars) = &parsed.variables && !vars.is_object() && !vars.is_null() {
+                return Err("GraphQL variables must be an object".to_string());
+            }
+            return Ok(parsed);
+        }
+
+        Ok(GraphqlPayload { query: payload.to_string(), operation_name: None, variables: None })
+    }
+}
+
+// Options for tcp/udp probe (fetch_api.options)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PayloadEncoding {
+    #[default]
+    Text,
+    Hex,
+    Base64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SocketOptions {
+    /// Encoding of fetch_api.payload
+    #[serde(default)]
+    pub encoding: PayloadEncoding,
+    /// Stop reading when reply contains this text
+    pub read_until: Option<String>,
+    /// Stop reading after this many bytes
+    pub read_bytes: Option<usize>,
+    /// Whole probe (connect, send, read) timeout in seconds, default SOCKET_TIMEOUT
+    pub timeout: Option<u64>,
+}
+impl SocketOptions {
+    pub fn parse(options: &Option<Value>) -> Result<Self, String> {
+        match options {
+            Some(val) if !val.is_null() => serde_json::from_value(val.clone())
+                .map_err(|e| format!("Invalid socket options: {}", e)),
+            _ => Ok(SocketOptions::default()),
+        }
+    }
+}
+
+// Options for tls inspection (fetch_api.options)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TlsOptions {
+    /// Fail run when leaf certificate expires in less than this days
+    #[serde(default = "TlsOptions::default_expiry_days")]
+    pub expiry_days: i64,
+    /// Fail run when chain not trusted (webpki roots)
+    #[serde(default = "TlsOptions::default_verify")]
+    pub verify: bool,
+    /// SNI override, default endpoint host
+    pub server_name: Option<String>,
+    /// Connect + handshake timeout in seconds, default SOCKET_TIMEOUT
+    pub timeout: Option<u64>,
+}
+impl TlsOptions {
+    fn default_expiry_days() -> i64 { 14 }
+    fn default_verify() -> bool { true }
+
+    pub fn parse(options: &Option<Value>) -> Result<Self, String> {
+        let val = match options {
+            Some(val) if !val.is_null() => val.clone(),
+            _ => Value::Object(Default::default()),
+        };
+        serde_json::from_value(val).map_err(|e| format!("Invalid tls options: {}", e))
+    }
+}
+
+// Options for dns lookup (fetch_api.options)
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum DnsRecordType {
+    #[default]
+    A,
+    Aaaa,
+    Cname,
+    Mx,
+    Txt,
+    Srv,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DnsExpectedMatch {
+    /// Answer set equal to expected
+    #[default]
+    Exact,
+    /// Answer set contains all expected
+    Contains,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DnsOptions {
+    #[serde(default)]
+    pub record_type: DnsRecordType,
+    /// ip or ip:port, default system resolver
+    pub nameserver: Option<String>,
+    /// Expected answer values, e.g. ["10 mail.example.com"] for MX
+    pub expected: Option<Vec<String>>,
+    #[serde(default)]
+    pub expected_match: DnsExpectedMatch,
+    /// Lookup timeout in seconds, default SOCKET_TIMEOUT
+    pub timeout: Option<u64>,
+}
+impl DnsOptions {
+    pub fn parse(options: &Option<Value>) -> Result<Self, String> {
+        match options {
+            Some(val) if !val.is_null() => serde_json::from_value(val.clone())
+                .map_err(|e| format!("Invalid dns options: {}", e)),
+            _ => Ok(DnsOptions::default()),
+        }
+    }
+}
+
+// Options for sql query (fetch_api.options)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SqlOptions {
+    /// Max rows stored, result flagged as truncated
+    #[serde(default = "SqlOptions::default_row_limit")]
+    pub row_limit: i64,
+    /// Statement timeout in seconds
+    #[serde(default = "SqlOptions::default_statement_timeout")]
+    pub statement_timeout: u64,
+}
+impl SqlOptions {
+    fn default_row_limit() -> i64 { 1000 }
+    fn default_statement_timeout() -> u64 { 30 }
+
+    pub fn parse(options: &Option<Value>) -> Result<Self, String> {
+        let val = match options {
+            Some(val) if !val.is_null() => val.clone(),
+            _ => Value::Object(Default::default()),
+        };
+        let parsed: SqlOptions = serde_json::from_value(val).map_err(|e| format!("Invalid sql options: {}", e))?;
+        if parsed.row_limit < 1 || parsed.statement_timeout < 1 {
+            return Err("Invalid sql options: row_limit and statement_timeout must be positive".to_string());
+        }
+
+        Ok(parsed)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum WsMode {
+    #[default]
+    Raw,
+    Socketio,
+}
+
+// Options for websocket (fetch_api.options)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WsOptions {
+    #[serde(default)]
+    pub mode: WsMode,
+    /// Steps run in order after connect, empty = send payload & listen until timeout.
+    /// Script run (connect included) stopped after 5 minutes
+    #[serde(default)]
+    pub steps: Vec<WsStep>,
+    #[serde(default)]
+    pub socketio: SocketIoOptions,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SocketIoOptions {
+    /// Engine.IO path, overrides endpoint path, default /socket.io/ on bare host
+    pub path: Option<String>,
+    /// Namespace, default /
+    pub namespace: Option<String>,
+    /// Auth payload sent on namespace connect
+    pub auth: Option<Value>,
+    /// Events emitted after connect
+    #[serde(default)]
+    pub emit: Vec<SocketIoEmit>,
+    /// Collected event names, empty = all
+    #[serde(default)]
+    pub events: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SocketIoEmit {
+    pub event: String,
+    #[serde(default)]
+    pub args: Vec<Value>,
+}
+
+/// Upper bound of sleep step in ms
+pub const MAX_WS_SLEEP_MS: u64 = 60_000;
+/// Upper bound of expect step timeout in seconds
+pub const MAX_WS_EXPECT_TIMEOUT: u64 = 120;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "lowercase")]
+pub enum WsStep {
+    /// Send text message, `{{name}}` replaced by saved value
+    Send { message: String },
+    /// Wait message matching regex pattern and/or json path (equals)
+    Expect {
+        pattern: Option<String>,
+        json_path: Option<String>,
+        equals: Option<Value>,
+        /// Step timeout in seconds, default WS_TIMEOUT, at most MAX_WS_EXPECT_TIMEOUT
+        timeout: Option<u64>,
+        /// Saved value name -> json path in matched message
+        #[serde(default)]
+        save: std::collections::HashMap<String, String>,
+    },
+    /// Pause in ms, at most MAX_WS_SLEEP_MS
+    Sleep { ms: u64 },
+}
+
+impl WsOptions {
+    pub fn parse(options: &Option<Value>) -> Result<Self, String> {
+        let parsed: WsOptions = match options {
+            Some(val) if !val.is_null() => serde_json::from_value(val.clone())
+                .map_err(|e| format!("Invalid websocket options: {}", e))?,
+            _ => WsOptions::default(),
+        };
+        if parsed.mode == WsMode::Socketio && !parsed.steps.is_empty() {
+            return Err("Invalid websocket options: steps are not supported in socketio mode".to_string());
+        }
+        if let Some(namespace) = &parsed.socketio.namespace && !namespace.starts_with('/') {
+            return Err("Invalid websocket options: socketio namespace must start with '/'".to_string());
+        }
+        if let Some(path) = &parsed.socketio.path && !path.starts_with('/') {
+            return Err("Invalid websocket options: socketio path must start with '/'".to_string());
+        }
+
+        for (i, step) in parsed.steps.iter().enumerate() {
+            match step {
+                WsStep::Expect { pattern, timeout, .. } => {
+                    if let Some(pattern) = pattern {
+                        regex::Regex::new(pattern).map_err(|e| format!("Invalid pattern in step {}: {}", i + 1, e))?;
+                    }
+                    if timeout.is_some_and(|t| t == 0 || t > MAX_WS_EXPECT_TIMEOUT) {
+                        return Err(format!("Invalid timeout in step {}: must be between 1 and {} seconds", i + 1, MAX_WS_EXPECT_TIMEOUT));
+                    }
+                }
+                WsStep::Sleep { ms } if *ms > MAX_WS_SLEEP_MS => {
+                    return Err(format!("Invalid sleep in step {}: must be at most {} ms", i + 1, MAX_WS_SLEEP_MS));
+                }
+                _ => {}
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+// Multipart part, text value or base64 file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultipartPart {
+    pub name: String,
+    pub value: Option<String>,
+    pub filename: Option<String>,
+    pub content_type: Option<String>,
+    pub content_base64: Option<String>,
+}
+
+// Http body by body_mode, parsed from payload
+#[derive(Debug, Clone)]
+pub enum RequestBody {
+    Empty,
+    Raw(String),
+    Json(Value),
+    Form(Vec<(String, String)>),
+    Multipart(Vec<MultipartPart>),
+}
+impl RequestBody {
+    pub fn parse(body_mode: &BodyMode, payload: &Option<String>) -> Result<Self, String> {
+        let payload = match payload {
+            Some(payload) if !payload.is_empty() => payload,
+            _ => return Ok(RequestBody::Empty),
+        };
+
+        match body_mode {
+            BodyMode::Raw => Ok(RequestBody::Raw(payload.clone())),
+            BodyMode::Json => serde_json::from_str(payload)
+                .map(RequestBody::Json)
+                .map_err(|e| format!("Invalid json body: {}", e)),
+            BodyMode::Form => {
+                let value: Value = serde_json::from_str(payload).map_err(|e| format!("Invalid form body: {}", e))?;
+                Ok(RequestBody::Form(value_to_pairs(&value, "form body")?))
+            }
+            BodyMode::Multipart => {
+                let parts: Vec<MultipartPart> = serde_json::from_str(payload)
+                    .map_err(|e| format!("Invalid multipart body, expected array of part: {}", e))?;
+                for part in &parts {
+                    if part.value.is_some() == part.content_base64.is_some() {
+                        return Err(format!("Invalid multipart part '{}': set either value or content_base64", part.name));
+                    }
+                }
+                Ok(RequestBody::Multipart(parts))
+            }
+        }
+    }
+}
+
+/// Query params from {"key": "v" | ["v1", "v2"] | number | bool}
+pub fn query_pairs(query: &Option<Value>) -> Result<Vec<(String, String)>, String> {
+    match query {
+        Some(val) if !val.is_null() => value_to_pairs(val, "query"),
+        _ => Ok(Vec::new()),
+    }
+}
+
+fn value_to_pairs(value: &Value, label: &str) -> Result<Vec<(String, String)>, String> {
+    let Value::Object(map) = value else {
+        return Err(format!("Invalid {}, expected object", label));
+    };
+
+    let mut pairs = Vec::new();
+    for (key, val) in map {
+        let values = match val {
+            Value::Array(items) => items.iter().collect(),
+            other => vec![other],
+        };
+        for item in values {
+            match item {
+                Value::String(s) => pairs.push((key.clone(), s.clone())),
+                Value::Number(_) | Value::Bool(_) => pairs.push((key.clone(), item.to_string())),
+                Value::Null => pairs.push((key.clone(), String::new())),
+                _ => return Err(format!("Invalid {} value for '{}', expected string", label, key)),
+            }
+        }
+    }
+
+    Ok(pairs)
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum HttpVersion {
+    #[default]
+    Auto,
+    Http1,
+    Http2,
+}
+
+/// Delay growth between retry attempts
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Backoff {
+    Fixed,
+    Linear,
+    #[default]
+    Exponential,
+}
+
+/// Failed run outcome
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RetryOn {
+    /// Request not done (connect, timeout, protocol error)
+    Network,
+    /// 5xx response
+    Server,
+    /// 429 response
+    RateLimit,
+    /// Response assertion failed
+    Assertion,
+}
+
+fn default_max_attempts() -> u32 { 10 }
+fn default_retry_delay() -> u64 { 1 }
+fn default_max_delay() -> Option<u64> { Some(300) }
+fn default_retry_on() -> Vec<RetryOn> { vec![RetryOn::Network] }
+fn default_true() -> bool { true }
+
+// Retry of failed run (fetch_api.retry, fetch_api_execute.retry)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RetryPolicy {
+    /// Attempts including first run, 1 = no retry
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    #[serde(default)]
+    pub backoff: Backoff,
+    /// Base delay in seconds
+    #[serde(default = "default_retry_delay")]
+    pub delay_seconds: u64,
+    /// Cap of backoff and Retry-After delay in seconds, null = server cap (1 day)
+    #[serde(default = "default_max_delay")]
+    pub max_delay_seconds: Option<u64>,
+    /// Random delay between half and full backoff
+    #[serde(default = "default_true")]
+    pub jitter: bool,
+    #[serde(default = "default_retry_on")]
+    pub retry_on: Vec<RetryOn>,
+    /// Wait at least Retry-After of 429 / 503 response, up to max_delay_seconds
+    #[serde(default = "default_true")]
+    pub respect_retry_after: bool,
+}
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_max_attempts(),
+            backoff: Backoff::default(),
+            delay_seconds: default_retry_delay(),
+            max_delay_seconds: default_max_delay(),
+            jitter: true,
+            retry_on: default_retry_on(),
+            respect_retry_after: true,
+        }
+    }
+}
+impl RetryPolicy {
+    pub fn parse(retry: &Option<Value>) -> Result<Self, String> {
+        let parsed: RetryPolicy = match retry {
+            Some(val) if !val.is_null() => serde_json::from_value(val.clone())
+                .map_err(|e| format!("Invalid retry policy: {}", e))?,
+            _ => RetryPolicy::default(),
+        };
+        if parsed.max_attempts == 0 {
+            return Err("Invalid retry policy: max_attempts must be positive".to_string());
+        }
+
+        Ok(parsed)
+    }
+
+    /// Fetch policy first, then execute policy
+    pub fn resolve(fetch: &Option<Value>, execute: &Option<Value>) -> Result<Self, String> {
+        match fetch {
+            Some(val) if !val.is_null() => Self::parse(fetch),
+            _ => Self::parse(execute),
+        }
+    }
+}
+
+/// Comparison of header / json path assertion
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AssertOp {
+    #[default]
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    /// Substring, or element of array
+    Contains,
+    Regex,
+    Exists,
+    NotExists,
+}
+
+/// Accepted status: code (200), class ("2xx") or range ("200-299")
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum StatusRule {
+    Code(u16),
+    Pattern(String),
+}
+impl StatusRule {
+    pub fn matches(&self, status: i16) -> Result<bool, String> {
+        let status = status as u16;
+        match self {
+            StatusRule::Code(code) => Ok(status == *code),
+            StatusRule::Pattern(pattern) => {
+                let pattern = pattern.trim().to_ascii_lowercase();
+                let invalid = || format!("Invalid status rule: {}", pattern);
+                if let Some((start, end)) = pattern.split_once('-') {
+                    let start: u16 = start.trim().parse().map_err(|_| invalid())?;
+                    let end: u16 = end.trim().parse().map_err(|_| invalid())?;
+                    return Ok((start..=end).contains(&status));
+                }
+                match pattern.strip_suffix("xx").map(str::parse::<u16>) {
+                    Some(Ok(class)) if (1..=5).contains(&class) => Ok(status / 100 == class),
+                    _ => Ok(status == pattern.parse::<u16>().map_err(|_| invalid())?),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeaderAssertion {
+    /// Header name, case insensitive
+    pub name: String,
+    #[serde(default)]
+    pub op: AssertOp,
+    #[serde(default)]
+    pub value: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonAssertion {
+    /// Simple json path of response body (`$.data.items[0].id`)
+    pub path: String,
+    #[serde(default)]
+    pub op: AssertOp,
+    #[serde(default)]
+    pub value: Value,
+}
+
+// Response assertions (fetch_api.assertions), every check must pass
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Assertions {
+    #[serde(default)]
+    pub status: Vec<StatusRule>,
+    #[serde(default)]
+    pub headers: Vec<HeaderAssertion>,
+    #[serde(default)]
+    pub json: Vec<JsonAssertion>,
+    pub max_latency_ms: Option<u64>,
+    /// JSON Schema of response body
+    pub schema: Option<Value>,
+}
+impl Assertions {
+    /// None when fetch has no assertion
+    pub fn parse(assertions: &Option<Value>) -> Result<Option<Self>, String> {
+        let parsed: Assertions = match assertions {
+            Some(val) if !val.is_null() => serde_json::from_value(val.clone())
+                .map_err(|e| format!("Invalid assertions: {}", e))?,
+            _ => return Ok(None),
+        };
+        for rule in &parsed.status {
+            rule.matches(200)?;
+        }
+        let patterns = parsed.headers.iter().map(|h| (h.op, &h.value))
+            .chain(parsed.json.iter().map(|j| (j.op, &j.value)));
+        for (op, value) in patterns {
+            if op == AssertOp::Regex {
+                let pattern = value.as_str().ok_or("Invalid assertions: regex value must be a string")?;
+                regex::Regex::new(pattern).map_err(|e| format!("Invalid assertions: {}", e))?;
+            }
+        }
+        if let Some(schema) = &parsed.schema {
+            jsonschema::validator_for(schema).map_err(|e| format!("Invalid assertions: json schema {}", e))?;
+        }
+
+        Ok(Some(parsed))
+    }
+}
+
+// Options for http client (rest, graphql), fetch_api.options
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct HttpOptions {
+    /// Request timeout in seconds, default HTTP_TIMEOUT
+    pub timeout: Option<u64>,
+    /// Max redirect followed, 0 = not follow, default 10
+    pub max_redirects: Option<usize>,
+    /// http://, https:// or socks5:// proxy url
+    pub proxy: Option<String>,
+    pub user_agent: Option<String>,
+    #[serde(default)]
+    pub http_version: HttpVersion,
+    /// Max response body stored, default 10 MiB, at most MAX_BODY_BYTES (truncated & flagged)
+    pub max_body_bytes: Option<usize>,
+    /// Skip certificate verification, internal host only (ignored with credential)
+    #[serde(default)]
+    pub accept_invalid_certs: bool,
+}
+impl HttpOptions {
+    pub fn parse(options: &Option<Value>) -> Result<Self, String> {
+        let parsed: HttpOptions = match options {
+            Some(val) if !val.is_null() => serde_json::from_value(val.clone())
+                .map_err(|e| format!("Invalid http options: {}", e))?,
+            _ => HttpOptions::default(),
+        };
+        if parsed.timeout == Some(0) {
+            return Err("Invalid http options: timeout must be positive".to_string());
+        }
+        if parsed.max_body_bytes == Some(0) {
+            return Err("Invalid http options: max_body_bytes must be positive".to_string());
+        }
+
+        Ok(parsed)
+    }
+}
@@ -1,20 +1,33 @@
-use sqlx::{PgPool};
-use crate::{models::fetch::{Api, ApiData, ApiExecute, ApiHeader, ApiMembers, CreateApi, CreateApiData, CreateApiExecute, CreateApiHeader, CreateApiMembers, UpdateApi, UpdateApiData, UpdateApiExecute, UpdateApiHeader, UpdateApiMembers}};
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::{PgConnection, PgPool, Postgres, Transaction};
+use crate::{models::fetch::{Api, ApiCalendar, ApiCredential, ApiData, ApiDataRaw, ApiExecute, ApiHeader, ApiMembers, ApiRun, CreateApi, CreateApiCalendar, CreateApiCredential, CreateApiData, CreateApiExecute, CreateApiHeader, CreateApiMembers, CreateApiRun, UpdateApi, UpdateApiCredential, UpdateApiData, UpdateApiExecute, UpdateApiHeader, UpdateApiMembers}};
 pub struct FetchRepository {
     pool: PgPool,
 }
 pub struct FetchMemberRepository {
     pool: PgPool
 }
+pub struct FetchCalendarRepository {
+    pool: PgPool
+}
 pub struct FetchExecuteRepository {
     pool: PgPool
 }
 pub struct FetchHeaderRepository {
     pool: PgPool
 }
+pub struct FetchCredentialRepository {
+    pool: PgPool
+}
 pub struct FetchDataRepository {
     pool: PgPool
 }
+pub struct FetchRunRepository {
+    pool: PgPool
+}
+
+/// Latest runs listed per fetch
+const MAX_RUNS_LISTED: i64 = 1000;
 
 impl FetchRepository {
     pub fn new(pool: PgPool) -> Self {
@@ -47,6 +60,25 @@ impl FetchRepository {
         .await
     }
 
+    pub async fn find_by_execute(&self, execute_id: i32) -> Result<Vec<Api>, sqlx::Error> {
+        sqlx::query_as::<_,Api>(
+            r#"SELECT * FROM fetch_api WHERE execute_id = $1 ORDER BY id ASC"#
+        )
+        .bind(execute_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Fetches of execute locked until transaction end
+    pub async fn lock_by_execute(&self, tx: &mut PgConnection, execute_id: i32) -> Result<Vec<Api>, sqlx::Error> {
+        sqlx::query_as::<_,Api>(
+            r#"SELECT * FROM fetch_api WHERE execute_id = $1 ORDER BY id ASC FOR UPDATE"#
+        )
+        .bind(execute_id)
+        .fetch_all(tx)
+        .await
+    }
+
     pub async fn get_all_fetch_user(&self, user_id: i32) -> Result<Vec<Api>, sqlx::Error> {
         sqlx::query_as::<_,Api>(     
         r#"
@@ -63,8 +95,8 @@ impl FetchRepository {
 
     pub async fn create(&self, data: CreateApi) -> Result<Api, sqlx::Error> {
         sqlx::query_as::<_,Api>(
-            r#"INSERT INTO fetch_api (name, type, endpoint, method, topic, description, payload, execute_id, header_id, is_active)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            r#"INSERT INTO fetch_api (name, type, endpoint, method, topic, description, payload, execute_id, header_id, is_active, options, query, body_mode, credential_id, retry, assertions)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, COALESCE($11, '{}'::jsonb), $12, COALESCE($13, 'raw'), $14, $15, $16)
             RETURNING *
             "#
         )
@@ -78,19 +110,124 @@ impl FetchRepository {
         .bind(data.execute_id)
         .bind(data.header_id)
         .bind(data.is_active)
+        .bind(data.options)
+        .bind(data.query)
+        .bind(data.body_mode)
+        .bind(data.credential_id)
+        .bind(data.retry)
+        .bind(data.assertions)
         .fetch_one(&self.pool)
         .await
     }
 
-    pub async fn update_job_id(&self,id: i32, job_id: String) -> Result<Api, sqlx::Error> {
+    pub async fn update_job_id(&self,id: i32, job_id: Option<String>, scheduled_at: Option<DateTime<Utc>>) -> Result<Api, sqlx::Error> {
+        let mut conn = self.pool.acquire().await?;
+        self.update_job_id_tx(&mut conn, id, job_id, scheduled_at).await
+    } 
+
+    /// `update_job_id` inside caller transaction
+    pub async fn update_job_id_tx(&self, tx: &mut PgConnection, id: i32, job_id: Option<String>, scheduled_at: Option<DateTime<Utc>>) -> Result<Api, sqlx::Error> {
         sqlx::query_as::<_,Api>(
-            r#"UPDATE fetch_api SET job_id=$2 WHERE id =$1 RETURNING *"#
+            r#"UPDATE fetch_api SET job_id=$2, scheduled_at=$3 WHERE id =$1 RETURNING *"#
+        )
+        .bind(id)
+        .bind(job_id)
+        .bind(scheduled_at)
+        .fetch_one(tx)
+        .await
+    }
+
+    /// Planned time of current job moved (misfire), kept for its retries
+    pub async fn update_scheduled_at(&self, id: i32, job_id: &str, scheduled_at: DateTime<Utc>) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"UPDATE fetch_api SET scheduled_at=$3 WHERE id=$1 AND job_id=$2"#
         )
         .bind(id)
         .bind(job_id)
+        .bind(scheduled_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn begin(&self) -> Result<Transaction<'static, Postgres>, sqlx::Error> {
+        self.pool.begin().await
+    }
+
+    /// Fetch row locked until transaction end, job changes (worker reschedule, pause, execute update) serialised
+    pub async fn lock_by_id(&self, tx: &mut PgConnection, id: i32) -> Result<Option<Api>, sqlx::Error> {
+        sqlx::query_as::<_,Api>(
+            r#"SELECT * FROM fetch_api WHERE id = $1 FOR UPDATE"#
+        )
+        .bind(id)
+        .fetch_optional(tx)
+        .await
+    }
+    
+    pub async fn increment_run_count(&self, id: i32) -> Result<Api, sqlx::Error> {
+        sqlx::query_as::<_,Api>(
+            r#"UPDATE fetch_api SET run_count = run_count + 1 WHERE id = $1 RETURNING *"#
+        )
+        .bind(id)
         .fetch_one(&self.pool)
         .await
-    } 
+    }
+
+    /// Stop schedule, pending job forgotten
+    pub async fn pause(&self, tx: &mut PgConnection, id: i32) -> Result<Api, sqlx::Error> {
+        sqlx::query_as::<_,Api>(
+            r#"UPDATE fetch_api SET is_active = FALSE, job_id = NULL, scheduled_at = NULL WHERE id = $1 RETURNING *"#
+        )
+        .bind(id)
+        .fetch_one(tx)
+        .await
+    }
+
+    pub async fn resume(&self, tx: &mut PgConnection, id: i32) -> Result<Api, sqlx::Error> {
+        sqlx::query_as::<_,Api>(
+            r#"UPDATE fetch_api SET is_active = TRUE WHERE id = $1 RETURNING *"#
+        )
+        .bind(id)
+        .fetch_one(tx)
+        .await
+    }
+
+    pub async fn add_missed_count(&self, id: i32, missed: i64) -> Result<Api, sqlx::Error> {
+        sqlx::query_as::<_,Api>(
+            r#"UPDATE fetch_api SET missed_count = missed_count + $2 WHERE id = $1 RETURNING *"#
+        )
+        .bind(id)
+        .bind(missed)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// New execute start counting from zero
+    pub async fn reset_run_count(&self, id: i32) -> Result<Api, sqlx::Error> {
+        let mut conn = self.pool.acquire().await?;
+        self.reset_run_count_tx(&mut conn, id).await
+    }
+
+    /// `reset_run_count` inside caller transaction
+    pub async fn reset_run_count_tx(&self, tx: &mut PgConnection, id: i32) -> Result<Api, sqlx::Error> {
+        sqlx::query_as::<_,Api>(
+            r#"UPDATE fetch_api SET run_count = 0 WHERE id = $1 RETURNING *"#
+        )
+        .bind(id)
+        .fetch_one(tx)
+        .await
+    }
+
+    pub async fn update_last_event_id(&self, id: i32, last_event_id: Option<String>) -> Result<Api, sqlx::Error> {
+        sqlx::query_as::<_,Api>(
+            r#"UPDATE fetch_api SET last_event_id=$2 WHERE id =$1 RETURNING *"#
+        )
+        .bind(id)
+        .bind(last_event_id)
+        .fetch_one(&self.pool)
+        .await
+    }
     
     pub async fn update(&self,id: &i32, data: UpdateApi) -> Result<Api, sqlx::Error> {
         sqlx::query_as::<_,Api>(
@@ -106,8 +243,14 @@ impl FetchRepository {
                         payload     = COALESCE($7, payload),
                         execute_id  = COALESCE($8, execute_id),
                         header_id   = COALESCE($9, header_id),
-                        is_active   = COALESCE($10, is_active)
-                    WHERE id = $11
+                        is_active   = COALESCE($10, is_active),
+                        options     = COALESCE($11, options),
+                        query       = COALESCE($12, query),
+                        body_mode   = COALESCE($13, body_mode),
+                        credential_id = COALESCE($14, credential_id),
+                        retry       = COALESCE($15, retry),
+                        assertions  = COALESCE($16, assertions)
+                    WHERE id = $17
                     RETURNING *
                 "#
         )
@@ -121,6 +264,12 @@ impl FetchRepository {
         .bind(data.execute_id)
         .bind(data.header_id)
         .bind(data.is_active)
+        .bind(data.options)
+        .bind(data.query)
+        .bind(data.body_mode)
+        .bind(data.credential_id)
+        .bind(data.retry)
+        .bind(data.assertions)
         .bind(id)
         .fetch_one(&self.pool)
         .await
@@ -139,6 +288,29 @@ impl FetchRepository {
         .await
     }   
 
+    /// Retry time / attempts of running apalis job, applied when job failed
+    /// Delete job not picked by a worker, false when running (left to finish)
+    pub async fn delete_idle_apalis_job(&self, tx: &mut PgConnection, job_id: &str) -> Result<bool, sqlx::Error> {
+        let status: Option<String> = sqlx::query_scalar(
+            r#"SELECT status FROM apalis.jobs WHERE id = $1 FOR UPDATE"#
+        )
+        .bind(job_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if status.as_deref() == Some("Running") {
+            return Ok(false);
+        }
+
+        sqlx::query(
+            r#"DELETE FROM apalis.jobs WHERE id = $1"#
+        )
+        .bind(job_id)
+        .execute(&mut *tx)
+        .await?;
+
+        Ok(true)
+    }
+
     pub async fn delete_apalis_job(&self, job_id: &String) -> Result<(), sqlx::Error> {
         sqlx::query(
             r#"
@@ -245,8 +417,8 @@ impl FetchExecuteRepository {
 
     pub async fn create(&self, data: CreateApiExecute) -> Result<ApiExecute, sqlx::Error> {
         sqlx::query_as::<_,ApiExecute> (
-            r#"INSERT INTO fetch_api_execute (user_id, name, is_repeat, type, value)
-            VALUES ($1, $2, $3, $4, $5)
+            r#"INSERT INTO fetch_api_execute (user_id, name, is_repeat, type, value, cron, rrule, solar, timezone, calendar_id, run_at, starts_at, ends_at, max_runs, misfire_policy, misfire_limit, retry)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, COALESCE($15, 'run_once'), $16, $17)
             RETURNING *
             "#
         )
@@ -255,15 +427,29 @@ impl FetchExecuteRepository {
         .bind(data.is_repeat)
         .bind(data.r#type)
         .bind(data.value)
+        .bind(data.cron)
+        .bind(data.rrule)
+        .bind(data.solar)
+        .bind(data.timezone)
+        .bind(data.calendar_id)
+        .bind(data.run_at)
+        .bind(data.starts_at)
+        .bind(data.ends_at)
+        .bind(data.max_runs)
+        .bind(data.misfire_policy)
+        .bind(data.misfire_limit)
+        .bind(data.retry)
         .fetch_one(&self.pool)
         .await
     }
 
-    pub async fn update(&self, id: i32, data: UpdateApiExecute) -> Result<ApiExecute, sqlx::Error> {
+    pub async fn update(&self, tx: &mut PgConnection, id: i32, data: UpdateApiExecute) -> Result<ApiExecute, sqlx::Error> {
         sqlx::query_as::<_,ApiExecute>(
             r#" UPDATE fetch_api_execute
-            SET name=$1, is_repeat=$2, type=$3, value=$4
-            WHERE id = $5
+            SET name=$1, is_repeat=$2, type=$3, value=$4, cron=$5, rrule=$6, solar=$7, timezone=$8, calendar_id=$9,
+                run_at=$10, starts_at=$11, ends_at=$12, max_runs=$13,
+                misfire_policy=COALESCE($14, misfire_policy), misfire_limit=$15, retry=$16
+            WHERE id = $17
             RETURNING *
             "#
         )
@@ -271,8 +457,20 @@ impl FetchExecuteRepository {
         .bind(data.is_repeat)
         .bind(data.r#type)
         .bind(data.value)
+        .bind(data.cron)
+        .bind(data.rrule)
+        .bind(data.solar)
+        .bind(data.timezone)
+        .bind(data.calendar_id)
+        .bind(data.run_at)
+        .bind(data.starts_at)
+        .bind(data.ends_at)
+        .bind(data.max_runs)
+        .bind(data.misfire_policy)
+        .bind(data.misfire_limit)
+        .bind(data.retry)
         .bind(id)
-        .fetch_one(&self.pool)
+        .fetch_one(tx)
         .await
     }
 
@@ -347,6 +545,145 @@ impl FetchHeaderRepository {
     }
 }
 
+impl FetchCredentialRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self {pool}
+    }
+
+    pub async fn find_by_id(&self, id: i32) -> Result<ApiCredential, sqlx::Error> {
+        sqlx::query_as::<_, ApiCredential> (
+            r#"SELECT * FROM fetch_api_credential WHERE id = $1"#
+        )
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    pub async fn find_all(&self, user_id: i32) -> Result<Vec<ApiCredential>, sqlx::Error> {
+        sqlx::query_as::<_,ApiCredential> (
+            r#"SELECT * FROM fetch_api_credential WHERE user_id = $1"#
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn create(&self, data: CreateApiCredential) -> Result<ApiCredential, sqlx::Error>{
+        sqlx::query_as::<_,ApiCredential>(
+            r#"INSERT INTO fetch_api_credential (user_id, name, client_cert, client_key, ca_bundle, database_url)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#
+        )
+        .bind(data.user_id)
+        .bind(data.name)
+        .bind(data.client_cert)
+        .bind(data.client_key)
+        .bind(data.ca_bundle)
+        .bind(data.database_url)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Empty field keep stored value, key & database_url only replaced on re-upload
+    pub async fn update(&self,id: i32, data: UpdateApiCredential) -> Result<ApiCredential, sqlx::Error>{
+        sqlx::query_as::<_,ApiCredential>(
+            r#"UPDATE fetch_api_credential
+            SET
+                name        = COALESCE($1, name),
+                client_cert = COALESCE($2, client_cert),
+                client_key  = COALESCE($3, client_key),
+                ca_bundle   = COALESCE($4, ca_bundle),
+                database_url = COALESCE($5, database_url)
+            WHERE id=$6
+            RETURNING *
+            "#
+        )
+        .bind(data.name)
+        .bind(data.client_cert)
+        .bind(data.client_key)
+        .bind(data.ca_bundle)
+        .bind(data.database_url)
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    pub async fn delete(&self, id:i32) -> Result<ApiCredential, sqlx::Error> {
+        sqlx::query_as::<_,ApiCredential> (
+            r#"DELETE FROM fetch_api_credential WHERE id=$1 RETURNING *"#
+        )
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await
+    }
+}
+
+impl FetchCalendarRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self {pool}
+    }
+
+    pub async fn find_by_id(&self, id: i32) -> Result<ApiCalendar, sqlx::Error> {
+        sqlx::query_as::<_, ApiCalendar> (
+            r#"SELECT * FROM fetch_api_calendar WHERE id = $1"#
+        )
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    pub async fn find_all(&self, user_id: i32) -> Result<Vec<ApiCalendar>, sqlx::Error> {
+        sqlx::query_as::<_,ApiCalendar> (
+            r#"SELECT * FROM fetch_api_calendar WHERE user_id = $1"#
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn create(&self, data: CreateApiCalendar) -> Result<ApiCalendar, sqlx::Error>{
+        sqlx::query_as::<_,ApiCalendar>(
+            r#"INSERT INTO fetch_api_calendar (user_id, name, dates)
+            VALUES ($1, $2, $3)
+            RETURNING *
+            "#
+        )
+        .bind(data.user_id)
+        .bind(data.name)
+        .bind(data.dates)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Empty field keep stored value
+    pub async fn update(&self, id: i32, name: Option<String>, dates: Option<Vec<NaiveDate>>) -> Result<ApiCalendar, sqlx::Error>{
+        sqlx::query_as::<_,ApiCalendar>(
+            r#"UPDATE fetch_api_calendar
+            SET
+                name  = COALESCE($1, name),
+                dates = COALESCE($2, dates)
+            WHERE id=$3
+            RETURNING *
+            "#
+        )
+        .bind(name)
+        .bind(dates)
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    pub async fn delete(&self, id:i32) -> Result<ApiCalendar, sqlx::Error> {
+        sqlx::query_as::<_,ApiCalendar> (
+            r#"DELETE FROM fetch_api_calendar WHERE id=$1 RETURNING *"#
+        )
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await
+    }
+}
+
 impl FetchDataRepository {
     pub fn new(pool: PgPool) -> Self {
         Self {pool}
@@ -361,6 +698,16 @@ impl FetchDataRepository {
         .await
     }
 
+    pub async fn find_raw(&self, fetch_id: i32, id: i32) -> Result<ApiDataRaw, sqlx::Error> {
+        sqlx::query_as::<_, ApiDataRaw>(
+            r#"SELECT response, response_raw, content_type FROM fetch_api_data WHERE id = $1 AND fetch_id = $2"#
+        )
+        .bind(id)
+        .bind(fetch_id)
+        .fetch_one(&self.pool)
+        .await
+    }
+
     pub async fn find_all(&self, fetch_id: i32) -> Result<Vec<ApiData>, sqlx::Error> {
         sqlx::query_as::<_,ApiData> (
             r#"SELECT * FROM fetch_api_data WHERE fetch_id = $1 ORDER BY updated_at DESC"#
@@ -372,8 +719,8 @@ impl FetchDataRepository {
 
     pub async fn create(&self, data: CreateApiData) -> Result<ApiData, sqlx::Error>{
         sqlx::query_as::<_,ApiData> (
-            r#"INSERT INTO fetch_api_data (fetch_id, name, status_code, response, response_headers)
-            VALUES ($1, $2, $3, $4, $5)
+            r#"INSERT INTO fetch_api_data (fetch_id, name, status_code, response, response_headers, meta, content_type, response_raw, assertions)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
             RETURNING *
             "#
         )
@@ -382,6 +729,10 @@ impl FetchDataRepository {
         .bind(data.status_code)
         .bind(data.response)
         .bind(data.response_headers)
+        .bind(data.meta)
+        .bind(data.content_type)
+        .bind(data.response_raw)
+        .bind(data.assertions)
         .fetch_one(&self.pool)
         .await
     }
@@ -411,3 +762,39 @@ impl FetchDataRepository {
         .await
     }
 }
+
+
+impl FetchRunRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self {pool}
+    }
+
+    pub async fn find_all(&self, fetch_id: i32) -> Result<Vec<ApiRun>, sqlx::Error> {
+        sqlx::query_as::<_,ApiRun>(
+            r#"SELECT * FROM fetch_api_runs WHERE fetch_id = $1 ORDER BY started_at DESC, id DESC LIMIT $2"#
+        )
+        .bind(fetch_id)
+        .bind(MAX_RUNS_LISTED)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn create(&self, data: CreateApiRun) -> Result<ApiRun, sqlx::Error> {
+        sqlx::query_as::<_,ApiRun>(
+            r#"INSERT INTO fetch_api_runs (fetch_id, job_id, planned_at, started_at, attempt, outcome, error, data_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING *
+            "#
+        )
+        .bind(data.fetch_id)
+        .bind(data.job_id)
+        .bind(data.planned_at)
+        .bind(data.started_at)
+        .bind(data.attempt)
+        .bind(data.outcome)
+        .bind(data.error)
+        .bind(data.data_id)
+        .fetch_one(&self.pool)
+        .await
+    }
+}
\ No newline at end of file
@@ -10,6 +10,9 @@ pub fn routes() -> Router<AppState> {
         .route("/fetch/{id}", get(get_fetch_api))
         .route("/fetch/{id}", patch(update_fetch_api))
         .route("/fetch/{id}", delete(delete_fetch_api))
+        .route("/fetch/{id}/pause", post(pause_fetch_api))
+        .route("/fetch/{id}/resume", post(resume_fetch_api))
+        .route("/fetch/{id}/run", post(run_fetch_api))
 
         .route("/fetch/{job_id}/job", get(get_fetch_job))
 
@@ -22,19 +25,34 @@ pub fn routes() -> Router<AppState> {
         .route("/fetch/{fetch_id}/data", post(create_fetch_data))
         .route("/fetch/{fetch_id}/data", get(get_all_data))
         .route("/fetch/{fetch_id}/data/{id}", get(get_fetch_data))
+        .route("/fetch/{fetch_id}/data/{id}/raw", get(get_fetch_data_raw))
         .route("/fetch/{fetch_id}/data/{id}", patch(update_fetch_data))
         .route("/fetch/{fetch_id}/data/{id}", delete(delete_fetch_data))
 
+        .route("/fetch/{fetch_id}/runs", get(get_all_runs))
+
         .route("/fetch/execute", get(get_all_execute))
         .route("/fetch/execute", post(create_fetch_execute))
         .route("/fetch/execute/{id}", get(get_fetch_execute))
         .route("/fetch/execute/{id}", patch(update_fetch_execute))
         .route("/fetch/execute/{id}", delete(delete_fetch_execute))
 
+        .route("/fetch/calendar", get(get_all_calendar))
+        .route("/fetch/calendar", post(create_fetch_calendar))
+        .route("/fetch/calendar/{id}", get(get_fetch_calendar))
+        .route("/fetch/calendar/{id}", patch(update_fetch_calendar))
+        .route("/fetch/calendar/{id}", delete(delete_fetch_calendar))
+
         .route("/fetch/header", get(get_all_header))
         .route("/fetch/header", post(create_fetch_header))
         .route("/fetch/header/{id}", get(get_fetch_header))
         .route("/fetch/header/{id}", patch(update_fetch_header))
         .route("/fetch/header/{id}", delete(delete_fetch_header))
 
+        .route("/fetch/credential", get(get_all_credential))
+        .route("/fetch/credential", post(create_fetch_credential))
+        .route("/fetch/credential/{id}", get(get_fetch_credential))
+        .route("/fetch/credential/{id}", patch(update_fetch_credential))
+        .route("/fetch/credential/{id}", delete(delete_fetch_credential))
+
 }
\ No newline at end of file
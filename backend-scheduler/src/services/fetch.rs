@@ -1,16 +1,23 @@
-use apalis::prelude::Storage;
+use apalis::prelude::{Request, Storage};
+use apalis_sql::context::SqlContext;
 use axum::{extract::{FromRef, FromRequestParts}, http::request::Parts};
-use chrono::{Utc, Duration};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde_json::Value;
+use sqlx::postgres::PgConnectOptions;
+use std::str::FromStr;
 use tracing::{warn,info};
-use crate::{models::{fetch::{Api, ApiData, ApiDataResponse, ApiExecute, ApiHeader, ApiMembers, CreateApiData, CreateApiExecute, CreateApiHeader, CreateApiMembers, ExecuteType, ReqCreateApi, ReqCreateApiData, ReqCreateApiExecute, ReqCreateApiHeader, Role, UpdateApi, UpdateApiData, UpdateApiExecute, UpdateApiHeader, UpdateApiMembers}, user::User}, repository::fetch::{FetchDataRepository, FetchExecuteRepository, FetchHeaderRepository, FetchMemberRepository, FetchRepository}, state::AppState, utils::response::AppError};
+use crate::{models::{fetch::{Api, ApiData, ApiType, BodyMode, DnsOptions, HttpOptions, RequestBody, query_pairs, GraphqlPayload, MqttTopic, SocketOptions, SqlOptions, TlsOptions, WsOptions, ApiDataResponse, ApiCalendar, ApiCredentialResponse, ApiExecute, Assertions, ApiExecuteCascade, ApiHeader, ApiMembers, ApiRun, CreateApiCalendar, CreateApiCredential, CreateApiData, CreateApiExecute, CreateApiHeader, CreateApiMembers, ReqCreateApi, ReqCreateApiData, ReqCreateApiCalendar, ReqCreateApiCredential, ReqCreateApiExecute, ReqCreateApiHeader, RetryPolicy, Role, UpdateApi, UpdateApiCalendar, UpdateApiCredential, UpdateApiData, UpdateApiExecute, UpdateApiHeader, UpdateApiMembers}, user::User}, jobs::socket::decode_payload, repository::fetch::{FetchCalendarRepository, FetchCredentialRepository, FetchDataRepository, FetchExecuteRepository, FetchHeaderRepository, FetchMemberRepository, FetchRepository, FetchRunRepository}, state::AppState, utils::{calendar::parse_ics, response::AppError, schedule::{self, plan_run}, tls::client_config_from_pem}};
 
 #[allow(dead_code)]
 pub struct FetchService {
     fetch_repo: FetchRepository,
     member_repo: FetchMemberRepository,
     execute_repo: FetchExecuteRepository,
+    calendar_repo: FetchCalendarRepository,
     header_repo: FetchHeaderRepository,
+    credential_repo: FetchCredentialRepository,
     data_repo: FetchDataRepository,
+    run_repo: FetchRunRepository,
     state: AppState,
 }
 
@@ -19,33 +26,173 @@ impl FetchService {
         let fetch_repo = FetchRepository::new(state.database.clone());
         let member_repo = FetchMemberRepository::new(state.database.clone());
         let execute_repo = FetchExecuteRepository::new(state.database.clone());
+        let calendar_repo = FetchCalendarRepository::new(state.database.clone());
         let header_repo = FetchHeaderRepository::new(state.database.clone());
+        let credential_repo = FetchCredentialRepository::new(state.database.clone());
         let data_repo = FetchDataRepository::new(state.database.clone());
-        Self {fetch_repo, member_repo, execute_repo, header_repo, data_repo, state}
+        let run_repo = FetchRunRepository::new(state.database.clone());
+        Self {fetch_repo, member_repo, execute_repo, calendar_repo, header_repo, credential_repo, data_repo, run_repo, state}
     }
 
-    // Create apalis job
-    pub async fn create_apalis_job(&self, fetch: &Api, execute: ApiExecute) -> Result<String, AppError> {
-        let duration = match execute.r#type {
-            Some(ExecuteType::Seconds) => Duration::seconds(execute.value),
-            Some(ExecuteType::Minutes) => Duration::minutes(execute.value),
-            Some(ExecuteType::Hours)   => Duration::hours(execute.value),
-            Some(ExecuteType::Days)    => Duration::days(execute.value),
-            None                       => Duration::minutes(execute.value),
+    /// Holiday dates of execute calendar
+    pub async fn holidays(&self, execute: &ApiExecute) -> Result<Vec<NaiveDate>, AppError> {
+        Ok(match execute.calendar_id {
+            Some(calendar_id) => self.calendar_repo.find_by_id(calendar_id).await?.dates,
+            None => Vec::new(),
+        })
+    }
+
+    // Create apalis job after `previous` planned time (fixed rate) or now, returns job id and planned time.
+    // None when execute schedule is finished (ends_at / max_runs / one-shot done)
+    pub async fn create_apalis_job(&self, fetch: &Api, execute: ApiExecute, previous: Option<DateTime<Utc>>) -> Result<Option<(String, DateTime<Utc>)>, AppError> {
+        let holidays = self.holidays(&execute).await?;
+        let Some(scheduled_at) = plan_run(&execute, &holidays, fetch.run_count, previous, Utc::now())
+            .map_err(AppError::BadRequest)? else {
+            info!("Fetch {} schedule finished after {} runs", fetch.id, fetch.run_count);
+            return Ok(None);
         };
-        let run_at = (Utc::now() + duration).timestamp();
 
         let apalis = self.state.job_queue.clone()
-                .schedule(fetch.clone(), run_at)
+                .schedule_request(Self::job_request(fetch.clone(), &execute), scheduled_at.timestamp())
                 .await
                 .map_err(|e| {
                     tracing::error!("Failed to enqueue apalis job: {e}");
                     AppError::InternalError("Failed to create scheduler!".to_string())})?;
 
-        Ok(apalis.task_id.to_string())
+        Ok(Some((apalis.task_id.to_string(), scheduled_at)))
+    }
+
+    /// Apalis job of fetch, retry limit of its policy kept by the queue
+    fn job_request(fetch: Api, execute: &ApiExecute) -> Request<Api, SqlContext> {
+        let policy = RetryPolicy::resolve(&fetch.retry, &execute.retry).unwrap_or_else(|e| {
+            warn!("Fetch {} retry policy ignored: {}", fetch.id, e);
+            RetryPolicy::default()
+        });
+        let mut ctx = SqlContext::new();
+        ctx.set_max_attempts(policy.max_attempts as i32);
+
+        Request::new_with_ctx(fetch, ctx)
+    }
+
+    // Delete pending apalis job of fetch, failure only logged
+    async fn delete_job(&self, fetch: &Api) {
+        if let Some(j_id) = &fetch.job_id
+            && let Err(e) = self.fetch_repo.delete_apalis_job(j_id).await {
+            warn!("Failed delete apalis job {}, continue to next step\n error: {}", j_id, e);
+        }
+    }
+
+    // Owner / editor of fetch
+    async fn check_fetch_editor(&self, fetch_id: i32, user: &User) -> Result<(), AppError> {
+        if !user.is_superuser {
+            let member = self.member_repo.find_member_id(fetch_id, user.id)
+                .await
+                .map_err(|_| AppError::Forbidden("You are not allowed to manage this fetch!".to_string()))?;
+
+            if member.role == Some(Role::Viewer) {
+                return Err(AppError::Forbidden("Viewer not allowed to manage fetch api.".to_string()));
+            }
+        }
+
+        Ok(())
+    }
+
+    // Validate payload/topic/options by fetch type
+    fn validate_fetch(&self, r#type: &ApiType, payload: &Option<String>, topic: &Option<Value>, options: &Option<Value>, query: &Option<Value>, body_mode: &BodyMode) -> Result<(), AppError> {
+        match r#type {
+            ApiType::Mqtt => {
+                MqttTopic::parse(topic).map_err(AppError::BadRequest)?;
+            }
+            ApiType::Rest => {
+                self.validate_http_options(options)?;
+                query_pairs(query).map_err(AppError::BadRequest)?;
+                RequestBody::parse(body_mode, payload).map_err(AppError::BadRequest)?;
+            }
+            ApiType::Websocket => {
+                WsOptions::parse(options).map_err(AppError::BadRequest)?;
+            }
+            ApiType::Sse => {
+                self.validate_http_options(options)?;
+            }
+            ApiType::Graphql => {
+                self.validate_http_options(options)?;
+                GraphqlPayload::parse(payload).map_err(AppError::BadRequest)?;
+            }
+            ApiType::Tcp | ApiType::Udp => {
+                let socket = SocketOptions::parse(options).map_err(AppError::BadRequest)?;
+                decode_payload(payload, &socket.encoding).map_err(AppError::BadRequest)?;
+            }
+            ApiType::Tls => {
+                TlsOptions::parse(options).map_err(AppError::BadRequest)?;
+            }
+            ApiType::Dns => {
+                DnsOptions::parse(options).map_err(AppError::BadRequest)?;
+            }
+            ApiType::Sql => {
+                SqlOptions::parse(options).map_err(AppError::BadRequest)?;
+                if payload.as_deref().unwrap_or("").trim().is_empty() {
+                    return Err(AppError::BadRequest("SQL statement is required".to_string()));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Credential only usable by the owner
+    async fn check_credential(&self, credential_id: Option<i32>, user: &User) -> Result<(), AppError> {
+        let Some(c_id) = credential_id else {
+            return Ok(());
+        };
+
+        let credential = self.credential_repo.find_by_id(c_id)
+            .await
+            .map_err(|_| AppError::BadRequest("Credential ID not found. Please create credential first.".to_string()))?;
+        if credential.user_id != user.id && !user.is_superuser {
+            return Err(AppError::Forbidden("You do not have permission to use this credential.".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Holiday calendar only usable by the owner
+    async fn check_calendar(&self, calendar_id: Option<i32>, user: &User) -> Result<(), AppError> {
+        let Some(c_id) = calendar_id else {
+            return Ok(());
+        };
+
+        let calendar = self.calendar_repo.find_by_id(c_id)
+            .await
+            .map_err(|_| AppError::BadRequest("Calendar ID not found. Please import calendar first.".to_string()))?;
+        if calendar.user_id != user.id && !user.is_superuser {
+            return Err(AppError::Forbidden("You do not have permission to use this calendar.".to_string()));
+        }
+
+        Ok(())
+    }
+
+    fn validate_database_url(database_url: &Option<String>) -> Result<(), AppError> {
+        if let Some(url) = database_url {
+            PgConnectOptions::from_str(url).map_err(|_| AppError::BadRequest("Invalid database_url".to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    fn validate_http_options(&self, options: &Option<Value>) -> Result<(), AppError> {
+        let http = HttpOptions::parse(options).map_err(AppError::BadRequest)?;
+        let max_body_bytes = self.state.app_config.max_body_bytes;
+        if http.max_body_bytes.is_some_and(|max| max > max_body_bytes) {
+            return Err(AppError::BadRequest(format!("Invalid http options: max_body_bytes must not exceed {}", max_body_bytes)));
+        }
+        if let Some(proxy) = &http.proxy {
+            reqwest::Proxy::all(proxy).map_err(|e| AppError::BadRequest(format!("Invalid proxy: {}", e)))?;
+        }
+
+        Ok(())
     }
 
-    /// #API AREA
+    // #API AREA
 
     /// get all fetch user
     pub async fn get_all_fetch_user(&self, user: User) -> Result<Vec<Api>, AppError> {
@@ -100,6 +247,17 @@ impl FetchService {
     
     pub async fn create_fetch(&self, data: ReqCreateApi, user: User) -> Result<Api, AppError> {
         let model = data.into_model();
+        self.validate_fetch(
+            model.r#type.as_ref().unwrap_or(&ApiType::Rest),
+            &model.payload,
+            &model.topic,
+            &model.options,
+            &model.query,
+            model.body_mode.as_ref().unwrap_or(&BodyMode::Raw),
+        )?;
+        RetryPolicy::parse(&model.retry).map_err(AppError::BadRequest)?;
+        Assertions::parse(&model.assertions).map_err(AppError::BadRequest)?;
+        self.check_credential(model.credential_id, &user).await?;
         let fetch = self.fetch_repo.create(model)
             .await
             .map_err(|e|{
@@ -129,8 +287,8 @@ impl FetchService {
         if execute.user_id != user.id && !user.is_superuser {
             return Err(AppError::Forbidden("You do not have permission to use this execute.".to_string()));
         }
-        let job_id = self.create_apalis_job(&fetch, execute).await?;
-        let updated_fetch = self.fetch_repo.update_job_id(fetch.id, job_id).await?;
+        let (job_id, scheduled_at) = self.create_apalis_job(&fetch, execute, None).await?.unzip();
+        let updated_fetch = self.fetch_repo.update_job_id(fetch.id, job_id, scheduled_at).await?;
 
         Ok(updated_fetch)
     }
@@ -147,20 +305,50 @@ impl FetchService {
             }
         }
 
-        if let Some(exe_id) = data.execute_id {
-            let execute = self.execute_repo.find_by_id(exe_id).await?;
-            if execute.user_id != user.id && !user.is_superuser {
-                return Err(AppError::Forbidden("You do not have permission to use this execute.".to_string()));
-            }
-            
+        if data.r#type.is_some() || data.payload.is_some() || data.topic.is_some() || data.options.is_some() || data.query.is_some() || data.body_mode.is_some() {
             let fetch = self.fetch_repo.get_by_id(id).await?;
-            if let Some(j_id) = &fetch.job_id {
-                if let Err(e) = self.fetch_repo.delete_apalis_job(j_id).await {
-                    warn!("Failed delete apalis job {}, continue to next step\n error: {}", j_id, e);
+            let r#type = data.r#type.clone().unwrap_or(fetch.r#type);
+            let payload = data.payload.clone().or(fetch.payload);
+            let topic = data.topic.clone().or(fetch.topic);
+            let options = data.options.clone().or(fetch.options);
+            let query = data.query.clone().or(fetch.query);
+            let body_mode = data.body_mode.clone().unwrap_or(fetch.body_mode);
+            self.validate_fetch(&r#type, &payload, &topic, &options, &query, &body_mode)?;
+        }
+        RetryPolicy::parse(&data.retry).map_err(AppError::BadRequest)?;
+        Assertions::parse(&data.assertions).map_err(AppError::BadRequest)?;
+        self.check_credential(data.credential_id, &user).await?;
+
+        let execute = match data.execute_id {
+            Some(exe_id) => {
+                let execute = self.execute_repo.find_by_id(exe_id).await?;
+                if execute.user_id != user.id && !user.is_superuser {
+                    return Err(AppError::Forbidden("You do not have permission to use this execute.".to_string()));
                 }
+                Some(execute)
             }
-            let job_id = self.create_apalis_job(&fetch, execute).await?;
-            self.fetch_repo.update_job_id(*id, job_id).await?;
+            None => None,
+        };
+
+        // New execute or is_active toggled, pending job replaced (none while inactive)
+        let fetch = self.fetch_repo.get_by_id(id).await?;
+        let is_active = data.is_active.unwrap_or(fetch.is_active);
+        if execute.is_some() || is_active != fetch.is_active {
+            self.delete_job(&fetch).await;
+            let fetch = match execute {
+                Some(_) => self.fetch_repo.reset_run_count(*id).await?,
+                None => fetch,
+            };
+            let (job_id, scheduled_at) = if is_active {
+                let execute = match execute {
+                    Some(execute) => execute,
+                    None => self.execute_repo.find_by_id(fetch.execute_id).await?,
+                };
+                self.create_apalis_job(&fetch, execute, None).await?.unzip()
+            } else {
+                (None, None)
+            };
+            self.fetch_repo.update_job_id(*id, job_id, scheduled_at).await?;
         }
 
         let query = self.fetch_repo.update(id, data)
@@ -213,7 +401,73 @@ impl FetchService {
         
         Ok(query)
     }
-    /// # MEMBER AREA
+
+    /// Pause fetch, pending job removed
+    pub async fn pause_fetch(&self, id: i32, user: User) -> Result<Api, AppError> {
+        self.check_fetch_editor(id, &user).await?;
+        // Row locked, job set by a worker finishing a run is the one removed
+        let mut tx = self.fetch_repo.begin().await?;
+        let fetch = self.fetch_repo.lock_by_id(&mut tx, id)
+            .await?
+            .ok_or(AppError::NotFound("Fetch api not found.".to_string()))?;
+        if !fetch.is_active {
+            return Err(AppError::BadRequest("Fetch api already paused.".to_string()));
+        }
+
+        let paused = self.fetch_repo.pause(&mut tx, id).await?;
+        tx.commit().await?;
+        self.delete_job(&fetch).await;
+        info!("Fetch {} paused", id);
+
+        Ok(paused)
+    }
+
+    /// Resume paused fetch with a fresh job, run count kept
+    pub async fn resume_fetch(&self, id: i32, user: User) -> Result<Api, AppError> {
+        self.check_fetch_editor(id, &user).await?;
+        // Row locked until the new job is saved, a worker finishing a run waits and sees it
+        let mut tx = self.fetch_repo.begin().await?;
+        let fetch = self.fetch_repo.lock_by_id(&mut tx, id)
+            .await?
+            .ok_or(AppError::NotFound("Fetch api not found.".to_string()))?;
+        if fetch.is_active {
+            return Err(AppError::BadRequest("Fetch api is not paused.".to_string()));
+        }
+
+        // Job left by a run finishing while paused
+        self.delete_job(&fetch).await;
+        let fetch = self.fetch_repo.resume(&mut tx, id).await?;
+        let execute = self.execute_repo.find_by_id(fetch.execute_id).await?;
+        let (job_id, scheduled_at) = self.create_apalis_job(&fetch, execute, None).await?.unzip();
+        let resumed = self.fetch_repo.update_job_id_tx(&mut tx, id, job_id, scheduled_at).await?;
+        if let Err(e) = tx.commit().await {
+            self.delete_job(&resumed).await;
+            return Err(e.into());
+        }
+        info!("Fetch {} resumed", id);
+
+        Ok(resumed)
+    }
+
+    /// Enqueue extra run now, schedule (pending job, run count) untouched
+    pub async fn run_fetch(&self, id: i32, user: User) -> Result<Api, AppError> {
+        self.check_fetch_editor(id, &user).await?;
+        let fetch = self.fetch_repo.get_by_id(&id).await?;
+        let execute = self.execute_repo.find_by_id(fetch.execute_id).await?;
+
+        let job = Api { run_now: true, ..fetch.clone() };
+        let apalis = self.state.job_queue.clone()
+                .push_request(Self::job_request(job, &execute))
+                .await
+                .map_err(|e| {
+                    tracing::error!("Failed to enqueue apalis job: {e}");
+                    AppError::InternalError("Failed to run fetch!".to_string())})?;
+        info!("Fetch {} run now, job {}", id, apalis.task_id);
+
+        Ok(fetch)
+    }
+
+    // # MEMBER AREA
     
     /// Find member by id
     pub async fn find_member(&self, user: User, fetch_id: i32, id: i32) -> Result<ApiMembers, AppError>{
@@ -339,7 +593,15 @@ impl FetchService {
     }
 
     /// create execute data
-    pub async fn create_execute(&self, user: User, req: ReqCreateApiExecute) -> Result<ApiExecute, AppError> {
+    pub async fn create_execute(&self, user: User, mut req: ReqCreateApiExecute) -> Result<ApiExecute, AppError> {
+        if let Some(rrule) = &req.rrule {
+            req.rrule = Some(schedule::normalize_rrule(rrule, &req.timezone, Utc::now()).map_err(AppError::BadRequest)?);
+        }
+        schedule::validate(&req.r#type, &req.cron, &req.rrule, &req.solar, &req.timezone).map_err(AppError::BadRequest)?;
+        schedule::validate_bounds(&req.run_at, &req.starts_at, &req.ends_at, &req.max_runs, Utc::now()).map_err(AppError::BadRequest)?;
+        schedule::validate_misfire(&req.misfire_limit).map_err(AppError::BadRequest)?;
+        RetryPolicy::parse(&req.retry).map_err(AppError::BadRequest)?;
+        self.check_calendar(req.calendar_id, &user).await?;
         let model: CreateApiExecute = req.into_model(user.id);
         let create = self.execute_repo.create(model)
             .await?;
@@ -347,27 +609,149 @@ impl FetchService {
         Ok(create)
     }
 
-    /// update execute data
-    pub async fn update_execute(&self, user: User, id: i32, req: UpdateApiExecute) -> Result<ApiExecute, AppError> {
+    /// update execute data, fetches using it rescheduled
+    pub async fn update_execute(&self, user: User, id: i32, mut req: UpdateApiExecute) -> Result<ApiExecuteCascade, AppError> {
         let execute = self.execute_repo.find_by_id(id).await?;
         if !user.is_superuser && execute.user_id != user.id {
             return Err(AppError::Forbidden("You don't have permission to access this data".to_string()));
         }
-        
-        Ok(self.execute_repo.update(id, req).await?)
+        if let Some(rrule) = &req.rrule {
+            req.rrule = Some(schedule::normalize_rrule(rrule, &req.timezone, Utc::now()).map_err(AppError::BadRequest)?);
+        }
+        schedule::validate(&req.r#type, &req.cron, &req.rrule, &req.solar, &req.timezone).map_err(AppError::BadRequest)?;
+        // Past run_at kept as is (already run)
+        let run_at = if req.run_at != execute.run_at { req.run_at } else { None };
+        schedule::validate_bounds(&run_at, &req.starts_at, &req.ends_at, &req.max_runs, Utc::now()).map_err(AppError::BadRequest)?;
+        schedule::validate_misfire(&req.misfire_limit).map_err(AppError::BadRequest)?;
+        RetryPolicy::parse(&req.retry).map_err(AppError::BadRequest)?;
+        self.check_calendar(req.calendar_id, &user).await?;
+
+        // Execute & its fetches changed together, fetch rows locked against worker reschedule
+        let mut tx = self.fetch_repo.begin().await?;
+        let execute = self.execute_repo.update(&mut tx, id, req).await?;
+        // One-shot planned in the future not run yet, counted from zero
+        let reset_run_count = !execute.is_repeat && execute.run_at.is_some_and(|run_at| run_at > Utc::now());
+        let mut affected_fetches = Vec::new();
+        let mut created_jobs = Vec::new();
+        for fetch in self.fetch_repo.lock_by_execute(&mut tx, id).await? {
+            let fetch = match reset_run_count {
+                true => self.fetch_repo.reset_run_count_tx(&mut tx, fetch.id).await?,
+                false => fetch,
+            };
+            if !fetch.is_active {
+                continue;
+            }
+            let (job_id, scheduled_at) = match self.create_apalis_job(&fetch, execute.clone(), None).await {
+                Ok(job) => job.unzip(),
+                Err(e) => {
+                    warn!("Failed reschedule fetch {} to execute {}, old job kept\n error: {:?}", fetch.id, id, e);
+                    continue;
+                }
+            };
+            // Running job left to finish, its worker see the new job id and not reschedule
+            if let Some(old_job) = &fetch.job_id
+                && !self.fetch_repo.delete_idle_apalis_job(&mut tx, old_job).await? {
+                info!("Job {} of fetch {} is running, replaced after it finish", old_job, fetch.id);
+            }
+            self.fetch_repo.update_job_id_tx(&mut tx, fetch.id, job_id.clone(), scheduled_at).await?;
+            if let Some(job_id) = job_id {
+                created_jobs.push(job_id);
+                affected_fetches.push(fetch.id);
+            }
+        }
+        if let Err(e) = tx.commit().await {
+            for job_id in &created_jobs {
+                let _ = self.fetch_repo.delete_apalis_job(job_id).await;
+            }
+            return Err(e.into());
+        }
+        info!("Execute {} updated, {} fetch rescheduled", id, affected_fetches.len());
+
+        Ok(ApiExecuteCascade { execute, affected_fetches })
     }
 
-    /// delete execute data
+    /// delete execute data, refused while fetches still use it
     pub async fn delete_execute(&self, user: User, id: i32) -> Result<ApiExecute, AppError> {
         let execute = self.execute_repo.find_by_id(id).await?;
         if !user.is_superuser && execute.user_id != user.id {
             return Err(AppError::Forbidden("You don't have permission to access this data".to_string()));
         }
 
-        Ok(self.execute_repo.delete(id).await?)
+        let fetch_ids: Vec<i32> = self.fetch_repo.find_by_execute(id).await?.iter().map(|f| f.id).collect();
+        if !fetch_ids.is_empty() {
+            return Err(AppError::Conflict(format!("Execute is used by fetch {:?}. Move or delete them first.", fetch_ids)));
+        }
+        let execute = self.execute_repo.delete(id)
+            .await
+            .map_err(|e| {
+                // Fetch added meanwhile
+                if let Some(db_error) = e.as_database_error()
+                    && db_error.constraint() == Some("fk_fetch_execute") {
+                    return AppError::Conflict("Execute is used by fetch. Move or delete them first.".to_string());
+                }
+                AppError::from(e)
+            })?;
+        info!("Execute {} deleted", id);
+
+        Ok(execute)
+    }
+
+    // #Fetch Calendar Area
+
+    /// get one
+    pub async fn get_calendar(&self, user: User, id: i32) -> Result<ApiCalendar, AppError> {
+        let q = self.calendar_repo.find_by_id(id).await?;
+        if !user.is_superuser && q.user_id != user.id {
+            return Err(AppError::Forbidden("You don't have permission to access this data".to_string()));
+        }
+
+        Ok(q)
     }
 
-    /// #Fetch Header Area
+    /// get all calendars related with user
+    pub async fn get_all_calendar(&self, user: User) -> Result<Vec<ApiCalendar>, AppError> {
+        let q = self.calendar_repo.find_all(user.id).await?;
+
+        Ok(q)
+    }
+
+    /// Import holiday calendar from .ics
+    pub async fn create_calendar(&self, user: User, data: ReqCreateApiCalendar) -> Result<ApiCalendar, AppError> {
+        let dates = parse_ics(&data.ics).map_err(AppError::BadRequest)?;
+        let model = CreateApiCalendar { user_id: user.id, name: data.name, dates };
+        let q = self.calendar_repo.create(model).await?;
+
+        Ok(q)
+    }
+
+    /// Update calendar user, new .ics replace dates
+    pub async fn update_calendar(&self, user: User, id: i32, data: UpdateApiCalendar) -> Result<ApiCalendar, AppError> {
+        let calendar = self.calendar_repo.find_by_id(id).await?;
+
+        if !user.is_superuser && calendar.user_id != user.id {
+            return Err(AppError::Forbidden("You don't have permission to update this data".to_string()));
+        }
+
+        let dates = data.ics.as_deref().map(parse_ics).transpose().map_err(AppError::BadRequest)?;
+        let q = self.calendar_repo.update(id, data.name, dates).await?;
+
+        Ok(q)
+    }
+
+    /// Delete calendar user, execute using it no longer skip holidays
+    pub async fn delete_calendar(&self, user: User, id: i32) -> Result<ApiCalendar, AppError> {
+        let calendar = self.calendar_repo.find_by_id(id).await?;
+
+        if !user.is_superuser && calendar.user_id != user.id {
+            return Err(AppError::Forbidden("You don't have permission to delete this data".to_string()));
+        }
+
+        let q = self.calendar_repo.delete(id).await?;
+
+        Ok(q)
+    }
+
+    // #Fetch Header Area
     
     /// get one
     pub async fn get_header(&self, user: User, id: i32) -> Result<ApiHeader, AppError> {
@@ -420,7 +804,71 @@ impl FetchService {
         Ok(q)
     }
 
-    /// #Fetch Data Area
+    // #Fetch Credential Area
+
+    /// get one, private key not returned
+    pub async fn get_credential(&self, user: User, id: i32) -> Result<ApiCredentialResponse, AppError> {
+        let q = self.credential_repo.find_by_id(id).await?;
+        if !user.is_superuser && q.user_id != user.id {
+            return Err(AppError::Forbidden("You don't have permission to access this data".to_string()));
+        }
+
+        Ok(q.into())
+    }
+
+    /// get all credentials related with user
+    pub async fn get_all_credential(&self, user: User) -> Result<Vec<ApiCredentialResponse>, AppError> {
+        let q = self.credential_repo.find_all(user.id).await?;
+
+        Ok(q.into_iter().map(ApiCredentialResponse::from).collect())
+    }
+
+    /// Create credential user
+    pub async fn create_credential(&self, user: User, data: ReqCreateApiCredential) -> Result<ApiCredentialResponse, AppError> {
+        let model: CreateApiCredential = data.into_model(user.id);
+        if model.client_cert.is_none() && model.ca_bundle.is_none() && model.database_url.is_none() {
+            return Err(AppError::BadRequest("Credential need client_cert/client_key, ca_bundle or database_url".to_string()));
+        }
+        client_config_from_pem(&model.client_cert, &model.client_key, &model.ca_bundle).map_err(AppError::BadRequest)?;
+        Self::validate_database_url(&model.database_url)?;
+        let q = self.credential_repo.create(model).await?;
+
+        Ok(q.into())
+    }
+
+    /// Update credential user
+    pub async fn update_credential(&self, user: User, id: i32, data: UpdateApiCredential) -> Result<ApiCredentialResponse, AppError> {
+        let credential = self.credential_repo.find_by_id(id).await?;
+
+        if !user.is_superuser && credential.user_id != user.id {
+            return Err(AppError::Forbidden("You don't have permission to update this data".to_string()));
+        }
+
+        let client_cert = data.client_cert.clone().or(credential.client_cert);
+        let client_key = data.client_key.clone().or(credential.client_key);
+        let ca_bundle = data.ca_bundle.clone().or(credential.ca_bundle);
+        client_config_from_pem(&client_cert, &client_key, &ca_bundle).map_err(AppError::BadRequest)?;
+        Self::validate_database_url(&data.database_url)?;
+
+        let q = self.credential_repo.update(id,data).await?;
+
+        Ok(q.into())
+    }
+
+    /// Delete credential user
+    pub async fn delete_credential(&self, user: User, id: i32) -> Result<ApiCredentialResponse, AppError> {
+        let credential = self.credential_repo.find_by_id(id).await?;
+
+        if !user.is_superuser && credential.user_id != user.id {
+            return Err(AppError::Forbidden("You don't have permission to delete this data".to_string()));
+        }
+
+        let q = self.credential_repo.delete(id).await?;
+
+        Ok(q.into())
+    }
+
+    // #Fetch Data Area
     
     /// get one
     pub async fn get_data(&self, user: User, fetch_id: i32, id: i32) -> Result<ApiDataResponse, AppError> {
@@ -434,6 +882,29 @@ impl FetchService {
         Ok(ApiDataResponse::from(data))
     }
 
+    /// Original response bytes & content type of stored fetch data
+    pub async fn get_data_raw(&self, user: User, fetch_id: i32, id: i32) -> Result<(Option<String>, Vec<u8>), AppError> {
+        if !user.is_superuser {
+            self.member_repo.find_member_id(fetch_id, user.id)
+            .await.map_err(|_|{AppError::Forbidden("You don't have permission to access this data".to_string())})?;
+        }
+
+        let data = self.data_repo.find_raw(fetch_id, id).await?;
+        let bytes = data.response_raw.unwrap_or(data.response.into_bytes());
+
+        Ok((data.content_type, bytes))
+    }
+
+    /// Run history of fetch, latest first
+    pub async fn get_all_runs(&self, user: User, fetch_id: i32) -> Result<Vec<ApiRun>, AppError> {
+        if !user.is_superuser {
+            self.member_repo.find_member_id(fetch_id, user.id)
+                .await.map_err(|_| AppError::Forbidden("You don't have permission to access this data".to_string()))?;
+        }
+
+        Ok(self.run_repo.find_all(fetch_id).await?)
+    }
+
     /// get all fetch data related with fetch
     pub async fn get_all_data(&self, user: User, fetch_id: i32) -> Result<Vec<ApiDataResponse>, AppError> {
         if !user.is_superuser {
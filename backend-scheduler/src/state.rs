@@ -1,7 +1,7 @@
 use apalis_sql::postgres::PostgresStorage;
 use sqlx::PgPool;
 use std::sync::Arc;
-use crate::{models::fetch::Api, jobs::websocket::WsJobs};
+use crate::{models::fetch::Api, jobs::{dns::DnsJobs, http::HttpClients, mqtt::MqttJobs, socket::SocketJobs, sse::SseJobs, tls::TlsJobs, websocket::WsJobs}};
 
 #[derive(Clone)]
 pub struct AppConfig {
@@ -9,13 +9,19 @@ pub struct AppConfig {
     pub access_ttl: i64,
     pub refresh_ttl: i64,
     pub concurrency: u32,
+    pub max_body_bytes: usize,
 }
 
 #[derive(Clone)]
 pub struct AppState {
     pub app_config: Arc<AppConfig>,
     pub database: PgPool,
-    pub http_client: reqwest::Client,
+    pub http_client: HttpClients,
     pub ws_client: WsJobs,
+    pub mqtt_client: MqttJobs,
+    pub sse_client: SseJobs,
+    pub socket_client: SocketJobs,
+    pub tls_client: TlsJobs,
+    pub dns_client: DnsJobs,
     pub job_queue: PostgresStorage<Api>,
 }
\ No newline at end of file
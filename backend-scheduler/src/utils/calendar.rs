@@ -0,0 +1,117 @@
+use std::str::FromStr;
+use chrono::{Duration, NaiveDate};
+use rrule::RRuleSet;
+
+/// Max dates expanded from one recurring event (yearly holiday etc)
+const MAX_EVENT_RECURRENCE: u16 = 200;
+/// Max days of one multi day event
+const MAX_EVENT_DAYS: i64 = 366;
+
+/// Holiday dates (sorted, unique) from iCalendar text. Each VEVENT mark DTSTART date,
+/// all day event until DTEND (exclusive), RRULE/EXDATE of event expanded.
+pub fn parse_ics(ics: &str) -> Result<Vec<NaiveDate>, String> {
+    let mut dates: Vec<NaiveDate> = Vec::new();
+    let mut event: Option<Vec<(String, String)>> = None;
+    let mut events = 0;
+
+    for line in unfold(ics) {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let key = name.split(';').next().unwrap_or("").to_ascii_uppercase();
+        match (key.as_str(), value.trim()) {
+            ("BEGIN", "VEVENT") => event = Some(Vec::new()),
+            ("END", "VEVENT") => {
+                if let Some(props) = event.take() {
+                    dates.extend(event_dates(&props)?);
+                    events += 1;
+                }
+            }
+            _ => {
+                if let Some(props) = event.as_mut() {
+                    props.push((name.to_string(), value.trim().to_string()));
+                }
+            }
+        }
+    }
+    if events == 0 {
+        return Err("Invalid ics: no VEVENT found".to_string());
+    }
+
+    dates.sort();
+    dates.dedup();
+
+    Ok(dates)
+}
+
+/// Join folded lines (continuation start with space or tab)
+fn unfold(ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in ics.lines() {
+        match (raw.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(raw.trim_end().to_string()),
+        }
+    }
+
+    lines
+}
+
+fn event_dates(props: &[(String, String)]) -> Result<Vec<NaiveDate>, String> {
+    let find = |key: &str| props.iter().find(|(name, _)| name.split(';').next().unwrap_or("").eq_ignore_ascii_case(key));
+    let (_, dtstart_value) = find("DTSTART").ok_or("Invalid ics: VEVENT without DTSTART")?;
+    let start = parse_date(dtstart_value)?;
+
+    // All day event span until DTEND (exclusive)
+    let days = match find("DTEND") {
+        Some((_, end)) if dtstart_value.len() == 8 => (parse_date(end)? - start).num_days().clamp(1, MAX_EVENT_DAYS),
+        _ => 1,
+    };
+    let span = |first: NaiveDate| (0..days).map(move |d| first + Duration::days(d));
+
+    if find("RRULE").is_none() {
+        return Ok(span(start).collect());
+    }
+
+    // Recurring event, expanded on date (time part ignored)
+    let mut rule = format!("DTSTART:{}T000000Z", start.format("%Y%m%d"));
+    for (name, value) in props {
+        let key = name.split(';').next().unwrap_or("").to_ascii_uppercase();
+        match key.as_str() {
+            "RRULE" => rule.push_str(&format!("\nRRULE:{}", utc_until(value))),
+            "EXDATE" => {
+                for exdate in value.split(',') {
+                    rule.push_str(&format!("\nEXDATE:{}T000000Z", parse_date(exdate)?.format("%Y%m%d")));
+                }
+            }
+            _ => {}
+        }
+    }
+    let set = RRuleSet::from_str(&rule).map_err(|e| format!("Invalid ics RRULE: {}", e))?;
+
+    Ok(set.all(MAX_EVENT_RECURRENCE)
+        .dates
+        .into_iter()
+        .flat_map(|d| span(d.date_naive()))
+        .collect())
+}
+
+/// UNTIL as UTC date-time, expansion start is UTC
+fn utc_until(rule: &str) -> String {
+    rule.split(';')
+        .map(|part| match part.split_once('=') {
+            Some((key, until)) if key.eq_ignore_ascii_case("UNTIL") && until.len() == 8 => format!("UNTIL={}T235959Z", until),
+            Some((key, until)) if key.eq_ignore_ascii_case("UNTIL") && !until.ends_with('Z') => format!("UNTIL={}Z", until),
+            _ => part.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// DATE (20260101) or DATE-TIME (20260101T080000Z), date part only
+fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    let value = value.trim();
+    value.get(..8)
+        .and_then(|d| NaiveDate::parse_from_str(d, "%Y%m%d").ok())
+        .ok_or(format!("Invalid ics date: {}", value))
+}
@@ -0,0 +1,50 @@
+use serde_json::Value;
+
+/// Select value by simple json path: `$.data.items[0].id`, `data.name`, `$['key with space']`
+pub fn select<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    let path = path.trim();
+    let path = path.strip_prefix('$').unwrap_or(path);
+    let mut current = value;
+    let mut chars = path.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => continue,
+            '[' => {
+                let mut segment = String::new();
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        break;
+                    }
+                    segment.push(c);
+                }
+                let segment = segment.trim();
+                current = match segment.strip_prefix(['\'', '"']).and_then(|s| s.strip_suffix(['\'', '"'])) {
+                    Some(key) => current.get(key)?,
+                    None => current.get(segment.parse::<usize>().ok()?)?,
+                };
+            }
+            _ => {
+                let mut key = String::from(c);
+                while let Some(&next) = chars.peek() {
+                    if next == '.' || next == '[' {
+                        break;
+                    }
+                    key.push(next);
+                    chars.next();
+                }
+                current = current.get(key.as_str())?;
+            }
+        }
+    }
+
+    Some(current)
+}
+
+/// Value as plain string (string without quotes)
+pub fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
@@ -2,4 +2,9 @@ pub mod auth;
 pub mod requests;
 pub mod response;
 pub mod hash;
-pub mod reqwest;
\ No newline at end of file
+pub mod reqwest;
+pub mod json_path;
+pub mod tls;
+pub mod schedule;
+pub mod calendar;
+pub mod solar;
\ No newline at end of file
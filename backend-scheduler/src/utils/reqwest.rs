@@ -1,5 +1,5 @@
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
-use serde_json::Value;
+use serde_json::{Map, Value};
 use std::str::FromStr;
 
 pub async fn json_to_headermap(json_input: Option<Value>) -> HeaderMap {
@@ -24,4 +24,15 @@ pub async fn json_to_headermap(json_input: Option<Value>) -> HeaderMap {
     }
     
     headers
-}
\ No newline at end of file
+}
+
+pub fn headermap_to_json(headers: &HeaderMap) -> Value {
+    let mut headers_map = Map::new();
+    for (k, v) in headers {
+        let key_str = k.to_string();
+        let val_str = v.to_str().unwrap_or("").to_string();
+        headers_map.insert(key_str, Value::String(val_str));
+    }
+
+    Value::Object(headers_map)
+}
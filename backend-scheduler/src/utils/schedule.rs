@@ -0,0 +1,287 @@
+use std::str::FromStr;
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use croner::Cron;
+use rrule::RRuleSet;
+use serde_json::Value;
+use crate::{models::fetch::{ApiExecute, ExecuteType, MisfirePolicy, SolarOptions}, utils::solar::event_time};
+
+/// Occurrences checked per rrule lookup
+const MAX_RRULE_CANDIDATES: u16 = 500;
+/// Days checked for solar event (polar night / day)
+const MAX_SOLAR_DAYS: i64 = 370;
+/// Missed slots counted per misfire check
+const MAX_MISFIRE_SLOTS: usize = 100_000;
+
+/// Misfire decision of a late job
+#[derive(Debug, PartialEq)]
+pub struct Misfire {
+    /// Run the job now
+    pub run: bool,
+    /// Slots dropped (not run)
+    pub missed: i64,
+    /// Base of the next planned slot
+    pub previous: DateTime<Utc>,
+}
+
+/// Cron pattern, 5 fields (minute) or 6 fields (with seconds), POSIX weekday (0 = sunday)
+pub fn parse_cron(expression: &str) -> Result<Cron, String> {
+    Cron::from_str(expression.trim()).map_err(|e| format!("Invalid cron expression '{}': {}", expression, e))
+}
+
+/// IANA time zone name, UTC when empty
+pub fn parse_timezone(timezone: &Option<String>) -> Result<Tz, String> {
+    match timezone.as_deref().map(str::trim) {
+        None | Some("") => Ok(Tz::UTC),
+        Some(name) => name.parse::<Tz>().map_err(|_| format!("Invalid time zone: {}", name)),
+    }
+}
+
+/// RFC 5545 recurrence: DTSTART, RRULE and EXDATE lines
+pub fn parse_rrule(rrule: &str) -> Result<RRuleSet, String> {
+    RRuleSet::from_str(rrule.trim()).map_err(|e| format!("Invalid rrule: {}", e))
+}
+
+/// Accept bare `FREQ=...` line, DTSTART added when missing (local midnight of `now` in time zone)
+pub fn normalize_rrule(rrule: &str, timezone: &Option<String>, now: DateTime<Utc>) -> Result<String, String> {
+    let mut lines: Vec<String> = rrule.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(|l| if l.to_ascii_uppercase().starts_with("FREQ=") { format!("RRULE:{}", l) } else { l.to_string() })
+        .collect();
+    if !lines.iter().any(|l| l.to_ascii_uppercase().starts_with("DTSTART")) {
+        let tz = parse_timezone(timezone)?;
+        let date = now.with_timezone(&tz).format("%Y%m%d");
+        lines.insert(0, match tz {
+            Tz::UTC => format!("DTSTART:{}T000000Z", date),
+            tz => format!("DTSTART;TZID={}:{}T000000", tz.name(), date),
+        });
+    }
+    let rrule = lines.join("\n");
+    parse_rrule(&rrule)?;
+
+    Ok(rrule)
+}
+
+/// Check execute settings before saved
+pub fn validate(r#type: &Option<ExecuteType>, cron: &Option<String>, rrule: &Option<String>, solar: &Option<Value>, timezone: &Option<String>) -> Result<(), String> {
+    parse_timezone(timezone)?;
+    match r#type {
+        Some(ExecuteType::Cron) => {
+            let expression = cron.as_deref().ok_or("Cron expression is required for cron execute")?;
+            parse_cron(expression)?;
+        }
+        Some(ExecuteType::Rrule) => {
+            let rrule = rrule.as_deref().ok_or("RRULE is required for rrule execute")?;
+            parse_rrule(rrule)?;
+        }
+        Some(ExecuteType::Solar) => {
+            SolarOptions::parse(solar)?;
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Next run time after `after`, cron/rrule evaluated on wall clock of execute time zone (DST aware),
+/// solar on the sun position of the location.
+/// Run falling on holiday date (sorted, in execute time zone) moved to the next non holiday day,
+/// interval run resumes at midnight.
+pub fn next_run(execute: &ApiExecute, holidays: &[NaiveDate], after: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    next_occurrence(execute, holidays, after)?.ok_or("No next run, recurrence is finished".to_string())
+}
+
+/// Same as `next_run`, None when the recurrence has no more occurrence (rrule UNTIL / COUNT reached)
+pub fn next_occurrence(execute: &ApiExecute, holidays: &[NaiveDate], after: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, String> {
+    let timezone = parse_timezone(&execute.timezone)?;
+    let rrule = match execute.r#type {
+        Some(ExecuteType::Rrule) => Some(parse_rrule(execute.rrule.as_deref().ok_or("RRULE is empty")?)?),
+        _ => None,
+    };
+    let solar = match execute.r#type {
+        Some(ExecuteType::Solar) => Some(SolarOptions::parse(&execute.solar)?),
+        _ => None,
+    };
+    let step = |cursor: DateTime<Utc>| -> Result<Option<DateTime<Utc>>, String> {
+        if let Some(duration) = interval(execute) {
+            return Ok(Some(cursor + duration));
+        }
+        Ok(match (&execute.r#type, &rrule) {
+            (Some(ExecuteType::Rrule), Some(set)) => next_rrule(set, cursor),
+            (Some(ExecuteType::Solar), _) => Some(next_solar(solar.as_ref().ok_or("Solar settings are empty")?, &timezone, cursor)?),
+            _ => Some(next_cron(execute, &timezone, cursor)?),
+        })
+    };
+
+    let Some(mut next) = step(after)? else {
+        return Ok(None);
+    };
+    // One step per consecutive holiday
+    for _ in 0..=holidays.len() {
+        let date = next.with_timezone(&timezone).date_naive();
+        if holidays.binary_search(&date).is_err() {
+            return Ok(Some(next));
+        }
+        let day_start = start_of_day(&timezone, date + Duration::days(1));
+        next = match interval(execute) {
+            Some(_) => day_start,
+            None => match step(day_start - Duration::seconds(1))? {
+                Some(next) => next,
+                None => return Ok(None),
+            },
+        };
+    }
+
+    Err("No next run outside holiday calendar".to_string())
+}
+
+/// Run at or after `at` (first run of window), None when the recurrence is finished
+pub fn first_run(execute: &ApiExecute, holidays: &[NaiveDate], at: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, String> {
+    let before = at - interval(execute).unwrap_or(Duration::seconds(1));
+    next_occurrence(execute, holidays, before)
+}
+
+/// Next job time honoring run_at, starts_at, ends_at, max_runs and the end of recurrence; None when schedule is finished.
+/// With `previous` planned time the next slot follow it (fixed rate, no drift from run duration),
+/// slot already passed run immediately.
+pub fn plan_run(execute: &ApiExecute, holidays: &[NaiveDate], run_count: i64, previous: Option<DateTime<Utc>>, now: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, String> {
+    if execute.max_runs.is_some_and(|max| run_count >= max) {
+        return Ok(None);
+    }
+
+    let next = match (execute.run_at, execute.starts_at) {
+        // Absolute first run, late one run immediately
+        (Some(run_at), _) if run_count == 0 => Some(run_at.max(now)),
+        (Some(_), _) if !execute.is_repeat => return Ok(None),
+        (_, Some(starts_at)) if starts_at > now => first_run(execute, holidays, starts_at)?,
+        _ => next_occurrence(execute, holidays, previous.unwrap_or(now))?,
+    };
+    let Some(next) = next else {
+        return Ok(None);
+    };
+    if execute.ends_at.is_some_and(|ends_at| next > ends_at) {
+        return Ok(None);
+    }
+
+    Ok(Some(next))
+}
+
+/// Apply misfire policy to job planned at `planned` and started at `now`.
+/// Late when the next slot(s) already passed, checked slots capped at MAX_MISFIRE_SLOTS
+/// and stopped at the end of recurrence.
+pub fn misfire(execute: &ApiExecute, holidays: &[NaiveDate], planned: DateTime<Utc>, now: DateTime<Utc>) -> Result<Misfire, String> {
+    let mut slots = vec![planned];
+    let mut cursor = planned;
+    while slots.len() <= MAX_MISFIRE_SLOTS {
+        let Some(next) = next_occurrence(execute, holidays, cursor)? else {
+            break;
+        };
+        if next <= cursor || next > now || execute.ends_at.is_some_and(|ends_at| next > ends_at) {
+            break;
+        }
+        slots.push(next);
+        cursor = next;
+    }
+    let last = slots[slots.len() - 1];
+    let late = slots.len() as i64 - 1;
+    if late == 0 {
+        return Ok(Misfire { run: true, missed: 0, previous: planned });
+    }
+
+    Ok(match execute.misfire_policy {
+        MisfirePolicy::Skip => Misfire { run: false, missed: late + 1, previous: last },
+        MisfirePolicy::RunOnce => Misfire { run: true, missed: late, previous: last },
+        // Keep the latest `limit` slots, this run is the first of them
+        MisfirePolicy::RunAll => {
+            let limit = execute.misfire_limit.map_or(slots.len(), |limit| limit.max(1) as usize);
+            let dropped = slots.len().saturating_sub(limit);
+            Misfire { run: true, missed: dropped as i64, previous: slots[dropped] }
+        }
+    })
+}
+
+/// Misfire of one apalis attempt: only the first attempt is checked,
+/// a retry belong to the slot its first attempt was run for (`planned`)
+pub fn attempt_misfire(execute: &ApiExecute, holidays: &[NaiveDate], attempt: u32, planned: DateTime<Utc>, now: DateTime<Utc>) -> Result<Misfire, String> {
+    if attempt > 1 {
+        return Ok(Misfire { run: true, missed: 0, previous: planned });
+    }
+
+    misfire(execute, holidays, planned, now)
+}
+
+/// Check run_at / window / max_runs before saved
+pub fn validate_bounds(run_at: &Option<DateTime<Utc>>, starts_at: &Option<DateTime<Utc>>, ends_at: &Option<DateTime<Utc>>, max_runs: &Option<i64>, now: DateTime<Utc>) -> Result<(), String> {
+    if run_at.is_some_and(|run_at| run_at <= now) {
+        return Err("run_at must be in the future".to_string());
+    }
+    if let (Some(starts_at), Some(ends_at)) = (starts_at, ends_at)
+        && starts_at >= ends_at {
+        return Err("starts_at must be before ends_at".to_string());
+    }
+    if max_runs.is_some_and(|max| max < 1) {
+        return Err("max_runs must be positive".to_string());
+    }
+
+    Ok(())
+}
+
+/// Check misfire_limit before saved
+pub fn validate_misfire(misfire_limit: &Option<i32>) -> Result<(), String> {
+    if misfire_limit.is_some_and(|limit| limit < 1) {
+        return Err("misfire_limit must be positive".to_string());
+    }
+
+    Ok(())
+}
+
+/// Fixed interval of seconds / minutes / hours / days type
+fn interval(execute: &ApiExecute) -> Option<Duration> {
+    match execute.r#type {
+        Some(ExecuteType::Seconds) => Some(Duration::seconds(execute.value)),
+        Some(ExecuteType::Minutes) | None => Some(Duration::minutes(execute.value)),
+        Some(ExecuteType::Hours)   => Some(Duration::hours(execute.value)),
+        Some(ExecuteType::Days)    => Some(Duration::days(execute.value)),
+        _ => None,
+    }
+}
+
+fn start_of_day(timezone: &Tz, date: NaiveDate) -> DateTime<Utc> {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+    timezone.from_local_datetime(&midnight)
+        .earliest()
+        // Midnight skipped by DST
+        .unwrap_or_else(|| timezone.from_utc_datetime(&midnight))
+        .with_timezone(&Utc)
+}
+
+fn next_cron(execute: &ApiExecute, timezone: &Tz, after: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    let expression = execute.cron.as_deref().ok_or("Cron expression is empty")?;
+    let cron = parse_cron(expression)?;
+
+    cron.find_next_occurrence(&after.with_timezone(timezone), false)
+        .map(|next| next.with_timezone(&Utc))
+        .map_err(|e| format!("No next run for cron '{}': {}", expression, e))
+}
+
+/// None when UNTIL passed or COUNT used up
+fn next_rrule(set: &RRuleSet, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    set.clone()
+        .after(after.with_timezone(&rrule::Tz::UTC))
+        .all(MAX_RRULE_CANDIDATES)
+        .dates
+        .into_iter()
+        .map(|next| next.with_timezone(&Utc))
+        .find(|next| *next > after)
+}
+
+fn next_solar(options: &SolarOptions, timezone: &Tz, after: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    // Start before local date, offset may move event to another day
+    let start = after.with_timezone(timezone).date_naive() - Duration::days(2);
+
+    (0..MAX_SOLAR_DAYS)
+        .filter_map(|day| event_time(options, start + Duration::days(day)))
+        .find(|next| *next > after)
+        .ok_or("No next run for solar event at this location".to_string())
+}
@@ -0,0 +1,141 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use crate::models::fetch::{AsrMethod, PrayerMethod, SolarEvent, SolarOptions};
+
+/// Sun altitude at sunrise / sunset (refraction + sun radius)
+const RISE_SET_ANGLE: f64 = 0.833;
+/// Imsak before fajr
+const IMSAK_MINUTES: f64 = 10.0;
+
+enum Isha {
+    Angle(f64),
+    Minutes(f64),
+}
+
+/// (fajr angle, isha, maghrib angle)
+fn method_params(method: PrayerMethod) -> (f64, Isha, Option<f64>) {
+    match method {
+        PrayerMethod::Kemenag | PrayerMethod::Jakim | PrayerMethod::Singapore => (20.0, Isha::Angle(18.0), None),
+        PrayerMethod::Mwl => (18.0, Isha::Angle(17.0), None),
+        PrayerMethod::Isna => (15.0, Isha::Angle(15.0), None),
+        PrayerMethod::Egypt => (19.5, Isha::Angle(17.5), None),
+        PrayerMethod::Makkah => (18.5, Isha::Minutes(90.0), None),
+        PrayerMethod::Karachi => (18.0, Isha::Angle(18.0), None),
+        PrayerMethod::Tehran => (17.7, Isha::Angle(14.0), Some(4.5)),
+    }
+}
+
+/// Event time (with offset) of the solar day at date, computed offline (praytimes.org algorithm).
+/// None when the sun never reach the angle (high latitude).
+pub fn event_time(options: &SolarOptions, date: NaiveDate) -> Option<DateTime<Utc>> {
+    let sun = SunDay::new(date, options.latitude, options.longitude);
+    let (fajr_angle, isha, maghrib_angle) = method_params(options.method);
+    let asr_factor = match options.asr {
+        AsrMethod::Standard => 1.0,
+        AsrMethod::Hanafi => 2.0,
+    };
+
+    // Local solar hour, guess refined once
+    let compute = |guess: f64| -> Option<f64> {
+        match options.event {
+            SolarEvent::Imsak => Some(sun.angle_time(fajr_angle, guess, true)? - IMSAK_MINUTES / 60.0),
+            SolarEvent::Fajr => sun.angle_time(fajr_angle, guess, true),
+            SolarEvent::Sunrise => sun.angle_time(RISE_SET_ANGLE, guess, true),
+            SolarEvent::Dhuhr => Some(sun.mid_day(guess)),
+            SolarEvent::Asr => sun.asr_time(asr_factor, guess),
+            SolarEvent::Sunset => sun.angle_time(RISE_SET_ANGLE, guess, false),
+            SolarEvent::Maghrib => sun.angle_time(maghrib_angle.unwrap_or(RISE_SET_ANGLE), guess, false),
+            SolarEvent::Isha => match isha {
+                Isha::Angle(angle) => sun.angle_time(angle, guess, false),
+                Isha::Minutes(minutes) => Some(sun.angle_time(RISE_SET_ANGLE, guess, false)? + minutes / 60.0),
+            },
+        }
+    };
+    let guess = match options.event {
+        SolarEvent::Imsak | SolarEvent::Fajr => 5.0,
+        SolarEvent::Sunrise => 6.0,
+        SolarEvent::Dhuhr => 12.0,
+        SolarEvent::Asr => 13.0,
+        SolarEvent::Sunset | SolarEvent::Maghrib | SolarEvent::Isha => 18.0,
+    };
+    let hours = compute(compute(guess)?)?;
+
+    // Local solar hour to UTC hour from midnight of date
+    let utc_hours = hours - options.longitude / 15.0;
+    let midnight = date.and_hms_opt(0, 0, 0)?.and_utc();
+
+    Some(midnight + Duration::milliseconds((utc_hours * 3_600_000.0).round() as i64) + Duration::minutes(options.offset_minutes))
+}
+
+struct SunDay {
+    jd: f64,
+    latitude: f64,
+}
+
+impl SunDay {
+    fn new(date: NaiveDate, latitude: f64, longitude: f64) -> Self {
+        Self {
+            jd: julian_day(date) - longitude / (15.0 * 24.0),
+            latitude,
+        }
+    }
+
+    /// (declination, equation of time) at local hour
+    fn position(&self, hours: f64) -> (f64, f64) {
+        let d = self.jd + hours / 24.0 - 2451545.0;
+        let g = fix_angle(357.529 + 0.98560028 * d);
+        let q = fix_angle(280.459 + 0.98564736 * d);
+        let l = fix_angle(q + 1.915 * dsin(g) + 0.020 * dsin(2.0 * g));
+        let e = 23.439 - 0.00000036 * d;
+
+        let ra = datan2(dcos(e) * dsin(l), dcos(l)) / 15.0;
+        let eqt = q / 15.0 - fix_hour(ra);
+        let decl = dasin(dsin(e) * dsin(l));
+
+        (decl, eqt)
+    }
+
+    fn mid_day(&self, hours: f64) -> f64 {
+        let (_, eqt) = self.position(hours);
+        fix_hour(12.0 - eqt)
+    }
+
+    /// Hour when sun at `angle` below horizon, before or after noon
+    fn angle_time(&self, angle: f64, hours: f64, before_noon: bool) -> Option<f64> {
+        let (decl, _) = self.position(hours);
+        let noon = self.mid_day(hours);
+        let cos_t = (-dsin(angle) - dsin(decl) * dsin(self.latitude)) / (dcos(decl) * dcos(self.latitude));
+        if !(-1.0..=1.0).contains(&cos_t) {
+            return None;
+        }
+        let t = cos_t.acos().to_degrees() / 15.0;
+
+        Some(if before_noon { noon - t } else { noon + t })
+    }
+
+    /// Asr when shadow = factor x object + noon shadow
+    fn asr_time(&self, factor: f64, hours: f64) -> Option<f64> {
+        let (decl, _) = self.position(hours);
+        let angle = -(1.0 / (factor + dtan((self.latitude - decl).abs()))).atan().to_degrees();
+        self.angle_time(angle, hours, false)
+    }
+}
+
+fn julian_day(date: NaiveDate) -> f64 {
+    let (mut year, mut month) = (date.year() as f64, date.month() as f64);
+    if month <= 2.0 {
+        year -= 1.0;
+        month += 12.0;
+    }
+    let a = (year / 100.0).floor();
+    let b = 2.0 - a + (a / 4.0).floor();
+
+    (365.25 * (year + 4716.0)).floor() + (30.6001 * (month + 1.0)).floor() + date.day() as f64 + b - 1524.5
+}
+
+fn dsin(d: f64) -> f64 { d.to_radians().sin() }
+fn dcos(d: f64) -> f64 { d.to_radians().cos() }
+fn dtan(d: f64) -> f64 { d.to_radians().tan() }
+fn dasin(x: f64) -> f64 { x.asin().to_degrees() }
+fn datan2(y: f64, x: f64) -> f64 { y.atan2(x).to_degrees() }
+fn fix_angle(a: f64) -> f64 { a - 360.0 * (a / 360.0).floor() }
+fn fix_hour(a: f64) -> f64 { a - 24.0 * (a / 24.0).floor() }
@@ -0,0 +1,74 @@
+use std::sync::Arc;
+use tokio_rustls::rustls::{self, ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme, client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier}, crypto::{WebPkiSupportedAlgorithms, verify_tls12_signature, verify_tls13_signature}, pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime, pem::PemObject}};
+use crate::models::fetch::ApiCredential;
+
+/// Rustls client config from credential: webpki roots + CA bundle, client cert for mTLS
+pub fn client_config(credential: &ApiCredential) -> Result<ClientConfig, String> {
+    client_config_from_pem(&credential.client_cert, &credential.client_key, &credential.ca_bundle)
+}
+
+pub fn client_config_from_pem(client_cert: &Option<String>, client_key: &Option<String>, ca_bundle: &Option<String>) -> Result<ClientConfig, String> {
+    let mut roots = RootCertStore::from_iter(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    if let Some(ca) = ca_bundle {
+        let (added, _) = roots.add_parsable_certificates(parse_certs(ca, "ca_bundle")?);
+        if added == 0 {
+            return Err("Invalid ca_bundle: no usable certificate".to_string());
+        }
+    }
+
+    let builder = ClientConfig::builder_with_provider(Arc::new(rustls::crypto::aws_lc_rs::default_provider()))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| format!("Failed build TLS config: {}", e))?
+        .with_root_certificates(roots);
+
+    match (client_cert, client_key) {
+        (Some(cert), Some(key)) => {
+            let key = PrivateKeyDer::from_pem_slice(key.as_bytes())
+                .map_err(|e| format!("Invalid client_key: {}", e))?;
+            builder.with_client_auth_cert(parse_certs(cert, "client_cert")?, key)
+                .map_err(|e| format!("Invalid client certificate: {}", e))
+        }
+        (None, None) => Ok(builder.with_no_client_auth()),
+        _ => Err("client_cert and client_key must be set together".to_string()),
+    }
+}
+
+/// Skip server certificate check (chain, name, expiry), handshake signature still verified
+pub fn accept_invalid_certs(config: &mut ClientConfig) {
+    let algorithms = config.crypto_provider().signature_verification_algorithms;
+    config.dangerous().set_certificate_verifier(Arc::new(AcceptAnyCert { algorithms }));
+}
+
+#[derive(Debug)]
+struct AcceptAnyCert {
+    algorithms: WebPkiSupportedAlgorithms,
+}
+
+impl ServerCertVerifier for AcceptAnyCert {
+    fn verify_server_cert(&self, _end_entity: &CertificateDer<'_>, _intermediates: &[CertificateDer<'_>], _server_name: &ServerName<'_>, _ocsp_response: &[u8], _now: UnixTime) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(&self, message: &[u8], cert: &CertificateDer<'_>, dss: &DigitallySignedStruct) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn verify_tls13_signature(&self, message: &[u8], cert: &CertificateDer<'_>, dss: &DigitallySignedStruct) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.algorithms.supported_schemes()
+    }
+}
+
+fn parse_certs(pem: &str, label: &str) -> Result<Vec<CertificateDer<'static>>, String> {
+    let certs = CertificateDer::pem_slice_iter(pem.as_bytes())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Invalid {}: {}", label, e))?;
+    if certs.is_empty() {
+        return Err(format!("Invalid {}: no PEM certificate", label));
+    }
+
+    Ok(certs)
+}
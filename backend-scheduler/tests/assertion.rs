@@ -0,0 +1,76 @@
+use scheduler::{jobs::assertion::{evaluate, failure_message}, models::fetch::{Assertions, FetchResult}};
+use serde_json::json;
+
+fn result(status_code: i16, body: &str) -> FetchResult {
+    FetchResult {
+        status_code,
+        headers: json!({"content-type": "application/json; charset=utf-8", "x-ratelimit-remaining": "42"}),
+        response: body.to_string(),
+        error: None,
+        meta: None,
+        content_type: Some("application/json".to_string()),
+        response_raw: None,
+    }
+}
+
+fn assertions(value: serde_json::Value) -> Assertions {
+    Assertions::parse(&Some(value)).unwrap().unwrap()
+}
+
+#[test]
+fn all_assertions_pass() {
+    let checks = assertions(json!({
+        "status": ["2xx", 304],
+        "headers": [
+            {"name": "Content-Type", "op": "contains", "value": "json"},
+            {"name": "x-ratelimit-remaining", "op": "gte", "value": 10},
+            {"name": "x-missing", "op": "not_exists"}
+        ],
+        "json": [
+            {"path": "$.status", "value": "ok"},
+            {"path": "$.data.count", "op": "gt", "value": 2},
+            {"path": "$.data.tags", "op": "contains", "value": "prod"},
+            {"path": "$.data.version", "op": "regex", "value": "^v\\d+\\.\\d+$"}
+        ],
+        "max_latency_ms": 500,
+        "schema": {"type": "object", "required": ["status", "data"], "properties": {"data": {"type": "object"}}}
+    }));
+    let body = r#"{"status": "ok", "data": {"count": 3, "tags": ["prod", "eu"], "version": "v1.2"}}"#;
+    let report = evaluate(&checks, &result(200, body), 120);
+
+    assert_eq!(report["passed"], true, "{}", report);
+    assert_eq!(report["results"].as_array().unwrap().len(), 10);
+    assert_eq!(failure_message(&report), None);
+}
+
+#[test]
+fn failed_assertions_reported() {
+    let checks = assertions(json!({
+        "status": ["200-299"],
+        "json": [{"path": "$.data.count", "op": "lt", "value": 10}],
+        "max_latency_ms": 100,
+        "schema": {"type": "object", "required": ["data"]}
+    }));
+    let report = evaluate(&checks, &result(503, r#"{"error": "maintenance"}"#), 250);
+    let results = report["results"].as_array().unwrap();
+
+    assert_eq!(report["passed"], false);
+    assert!(results.iter().all(|r| r["passed"] == false));
+    assert_eq!(results[0]["actual"], 503);
+    assert!(results[3]["message"].as_str().unwrap().contains("data"));
+    assert_eq!(failure_message(&report).unwrap(), "Assertion failed: status, json $.data.count, latency, schema");
+
+    // Non JSON body fail json / schema checks
+    let report = evaluate(&checks, &result(200, "<html>"), 10);
+    assert_eq!(report["results"][1]["passed"], false);
+    assert!(report["results"][1]["message"].as_str().unwrap().starts_with("Response body is not JSON"));
+}
+
+#[test]
+fn invalid_assertions_rejected() {
+    assert!(Assertions::parse(&None).unwrap().is_none());
+    assert!(Assertions::parse(&Some(json!({"status": ["2yy"]}))).is_err());
+    assert!(Assertions::parse(&Some(json!({"json": [{"path": "$.a", "op": "regex", "value": "("}]}))).is_err());
+    assert!(Assertions::parse(&Some(json!({"json": [{"path": "$.a", "op": "between"}]}))).is_err());
+    assert!(Assertions::parse(&Some(json!({"schema": {"type": "nope"}}))).is_err());
+}
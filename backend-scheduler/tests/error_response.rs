@@ -10,6 +10,7 @@ async fn test_api_error_response() {
         (AppError::InternalError("x".into()), StatusCode::INTERNAL_SERVER_ERROR),
         (AppError::BadRequest("x".into()), StatusCode::BAD_REQUEST),
         (AppError::Forbidden("x".into()), StatusCode::FORBIDDEN),
+        (AppError::Conflict("x".into()), StatusCode::CONFLICT),
         (AppError::AuthError("x".into()), StatusCode::UNAUTHORIZED)
     ];
 
@@ -0,0 +1,75 @@
+use axum::http;
+use scheduler::{jobs::http::{DEFAULT_MAX_BODY_BYTES, HttpClients, read_body}, models::fetch::HttpOptions};
+use serde_json::json;
+
+fn response(content_type: Option<&str>, body: &[u8]) -> reqwest::Response {
+    let mut builder = http::Response::builder().status(200);
+    if let Some(content_type) = content_type {
+        builder = builder.header("content-type", content_type);
+    }
+    reqwest::Response::from(builder.body(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn capture_text_and_truncate() {
+    let body = read_body(response(Some("application/json"), br#"{"ok":true}"#), 1024).await.unwrap();
+    assert_eq!(body.text, r#"{"ok":true}"#);
+    assert_eq!(body.raw, None);
+    assert_eq!(body.content_type.as_deref(), Some("application/json"));
+    assert_eq!(body.meta, json!({"size": 11, "truncated": false, "charset": "UTF-8", "encoding": "text"}));
+
+    // Rest of body dropped and flagged
+    let long = "a".repeat(100);
+    let body = read_body(response(Some("text/plain"), long.as_bytes()), 10).await.unwrap();
+    assert_eq!(body.text, "a".repeat(10));
+    assert_eq!((body.size, body.meta["truncated"].clone()), (10, json!(true)));
+}
+
+#[tokio::test]
+async fn detect_charset() {
+    // Latin-1 by charset param, original bytes kept
+    let latin = [b'c', b'a', b'f', 0xE9];
+    let body = read_body(response(Some("text/plain; charset=\"ISO-8859-1\""), &latin), 1024).await.unwrap();
+    assert_eq!(body.text, "café");
+    assert_eq!(body.raw.as_deref(), Some(&latin[..]));
+    assert_eq!(body.meta["charset"], json!("windows-1252"));
+
+    // BOM wins over charset param
+    let utf16 = [0xFF, 0xFE, b'h', 0, b'i', 0];
+    let body = read_body(response(Some("text/plain; charset=utf-8"), &utf16), 1024).await.unwrap();
+    assert_eq!((body.text.as_str(), body.meta["charset"].clone()), ("hi", json!("UTF-16LE")));
+
+    // Invalid utf-8 decoded lossy, raw kept for download
+    let body = read_body(response(Some("text/plain"), &[b'o', b'k', 0xFF]), 1024).await.unwrap();
+    assert_eq!(body.text, "ok\u{FFFD}");
+    assert!(body.raw.is_some());
+}
+
+#[tokio::test]
+async fn binary_or_text_content() {
+    let png = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+    let body = read_body(response(Some("image/png"), &png), 1024).await.unwrap();
+    assert_eq!(body.text, "");
+    assert_eq!(body.raw.as_deref(), Some(&png[..]));
+    assert_eq!(body.meta, json!({"size": 8, "truncated": false, "charset": null, "encoding": "binary"}));
+
+    // Without content type, text only when valid utf-8
+    let body = read_body(response(None, b"plain"), 1024).await.unwrap();
+    assert_eq!((body.text.as_str(), body.meta["encoding"].clone()), ("plain", json!("text")));
+    let body = read_body(response(None, &png), 1024).await.unwrap();
+    assert_eq!(body.meta["encoding"], json!("binary"));
+
+    // Vendor json type is text
+    let body = read_body(response(Some("application/vnd.api+json"), b"{}"), 1024).await.unwrap();
+    assert_eq!(body.text, "{}");
+}
+
+#[test]
+fn body_cap_within_server_ceiling() {
+    let clients = HttpClients::new(5, 1024);
+    let options = |max_body_bytes| HttpOptions { max_body_bytes, ..HttpOptions::default() };
+    assert_eq!(clients.body_cap(&options(None)), 1024);
+    assert_eq!(clients.body_cap(&options(Some(100))), 100);
+    assert_eq!(clients.body_cap(&options(Some(usize::MAX))), 1024);
+    assert_eq!(HttpClients::new(5, usize::MAX).body_cap(&options(None)), DEFAULT_MAX_BODY_BYTES);
+}
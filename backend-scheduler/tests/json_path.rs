@@ -0,0 +1,15 @@
+use scheduler::utils::json_path::{select, value_to_string};
+use serde_json::json;
+
+#[test]
+fn select_json_path() {
+    let value = json!({ "data": { "items": [{ "id": 7 }], "key with space": "ok" }, "type": "ack" });
+
+    assert_eq!(select(&value, "$.type"), Some(&json!("ack")));
+    assert_eq!(select(&value, "data.items[0].id"), Some(&json!(7)));
+    assert_eq!(select(&value, "$.data['key with space']"), Some(&json!("ok")));
+    assert_eq!(select(&value, "$.data.items[1]"), None);
+    assert_eq!(select(&value, "$"), Some(&value));
+    assert_eq!(value_to_string(&json!("abc")), "abc");
+    assert_eq!(value_to_string(&json!(12)), "12");
+}
@@ -0,0 +1,19 @@
+use scheduler::models::fetch::MqttTopic;
+use serde_json::json;
+
+#[test]
+fn validate_topic_config() {
+    let topic = MqttTopic::parse(&Some(json!({
+        "publish": [{"topic": "sensors/1/cmd", "qos": 1}],
+        "subscribe": [{"topic": "sensors/+/state", "qos": 2}, {"topic": "alerts/#"}],
+        "listen": 5
+    }))).unwrap();
+    assert_eq!((topic.publish.len(), topic.subscribe.len()), (1, 2));
+
+    assert!(MqttTopic::parse(&None).is_err());
+    assert!(MqttTopic::parse(&Some(json!({"publish": [{"topic": "a", "qos": 5}]}))).is_err());
+    assert!(MqttTopic::parse(&Some(json!({"subscribe": [{"topic": ""}]}))).is_err());
+    assert!(MqttTopic::parse(&Some(json!({"publish": [{"topic": "sensors/+/cmd"}]}))).is_err());
+    assert!(MqttTopic::parse(&Some(json!({"publish": [{"topic": "sensors/#"}]}))).is_err());
+    assert!(MqttTopic::parse(&Some(json!({"publish": "sensors"}))).is_err());
+}
@@ -0,0 +1,87 @@
+use chrono::{Duration, TimeZone, Utc};
+use scheduler::{jobs::retry::{MAX_RETRY_DELAY_SECONDS, failure, retry_after, retry_delay}, models::fetch::{Backoff, FetchResult, RetryOn, RetryPolicy}};
+use serde_json::json;
+
+fn result(status_code: i16, headers: serde_json::Value) -> FetchResult {
+    FetchResult {
+        status_code,
+        headers,
+        response: String::new(),
+        error: None,
+        meta: None,
+        content_type: None,
+        response_raw: None,
+    }
+}
+
+#[test]
+fn classify_failure() {
+    assert_eq!(failure(&result(200, json!({}))), None);
+    assert_eq!(failure(&result(404, json!({}))), None);
+    assert_eq!(failure(&result(429, json!({}))).map(|f| f.0), Some(RetryOn::RateLimit));
+    assert_eq!(failure(&result(503, json!({}))).map(|f| f.0), Some(RetryOn::Server));
+
+    let mut invalid = result(200, json!({}));
+    invalid.error = Some("Invalid GraphQL response [200]".to_string());
+    assert_eq!(failure(&invalid).map(|f| f.0), Some(RetryOn::Network));
+}
+
+#[test]
+fn parse_retry_after() {
+    let now = Utc.with_ymd_and_hms(2026, 10, 18, 7, 28, 0).unwrap();
+    assert_eq!(retry_after(&json!({"retry-after": "120"}), now), Some(Duration::seconds(120)));
+    assert_eq!(retry_after(&json!({"Retry-After": "Sun, 18 Oct 2026 07:30:00 GMT"}), now), Some(Duration::seconds(120)));
+    assert_eq!(retry_after(&json!({"retry-after": "Sun, 18 Oct 2026 07:00:00 GMT"}), now), Some(Duration::zero()));
+    assert_eq!(retry_after(&json!({"content-type": "text/plain"}), now), None);
+
+    // Oversized and overflowing values clamp to server cap
+    let max = Duration::seconds(MAX_RETRY_DELAY_SECONDS as i64);
+    assert_eq!(retry_after(&json!({"retry-after": "10000000000000"}), now), Some(max));
+    assert_eq!(retry_after(&json!({"retry-after": "99999999999999999"}), now), Some(max));
+    assert_eq!(retry_after(&json!({"retry-after": "999999999999999999999999"}), now), Some(max));
+    assert_eq!(retry_after(&json!({"retry-after": "Fri, 31 Dec 9999 23:59:59 GMT"}), now), Some(max));
+}
+
+#[test]
+fn backoff_delay() {
+    let mut policy = RetryPolicy { delay_seconds: 2, max_delay_seconds: Some(60), jitter: false, ..RetryPolicy::default() };
+    let delays = |policy: &RetryPolicy| (1..=7).map(|attempt| retry_delay(policy, attempt, None, 0.0).num_seconds()).collect::<Vec<_>>();
+    assert_eq!(delays(&policy), vec![2, 4, 8, 16, 32, 60, 60]);
+    policy.backoff = Backoff::Linear;
+    assert_eq!(delays(&policy), vec![2, 4, 6, 8, 10, 12, 14]);
+    policy.backoff = Backoff::Fixed;
+    assert_eq!(delays(&policy), vec![2; 7]);
+
+    // Equal jitter stays within half and full delay
+    policy.jitter = true;
+    assert_eq!(retry_delay(&policy, 1, None, 0.0), Duration::seconds(1));
+    assert_eq!(retry_delay(&policy, 1, None, 1.0), Duration::seconds(2));
+
+    // Retry-After above backoff, within cap
+    assert_eq!(retry_delay(&policy, 1, Some(Duration::seconds(45)), 0.5), Duration::seconds(45));
+    assert_eq!(retry_delay(&policy, 1, Some(Duration::seconds(600)), 0.5), Duration::seconds(60));
+    policy.respect_retry_after = false;
+    assert_eq!(retry_delay(&policy, 1, Some(Duration::seconds(600)), 1.0), Duration::seconds(2));
+
+    // Uncapped policy still bounded by server cap
+    let max = Duration::seconds(MAX_RETRY_DELAY_SECONDS as i64);
+    let uncapped = RetryPolicy { delay_seconds: u64::MAX / 2, max_delay_seconds: None, jitter: false, ..RetryPolicy::default() };
+    assert_eq!(retry_delay(&uncapped, 1, None, 0.0), max);
+    assert_eq!(retry_delay(&uncapped, 64, None, 0.0), max);
+    let uncapped = RetryPolicy { max_delay_seconds: None, jitter: false, ..RetryPolicy::default() };
+    assert_eq!(retry_delay(&uncapped, 1, retry_after(&json!({"retry-after": "99999999999999999"}), Utc::now()), 0.0), max);
+}
+
+#[test]
+fn parse_policy() {
+    let policy = RetryPolicy::parse(&Some(json!({"max_attempts": 3, "backoff": "linear", "retry_on": ["network", "rate_limit"]}))).unwrap();
+    assert_eq!(policy.max_attempts, 3);
+    assert_eq!(policy.retry_on, vec![RetryOn::Network, RetryOn::RateLimit]);
+    assert!(RetryPolicy::parse(&Some(json!({"max_attempts": 0}))).is_err());
+    assert!(RetryPolicy::parse(&Some(json!({"retry_on": ["teapot"]}))).is_err());
+
+    // Fetch policy override execute policy
+    let execute = Some(json!({"max_attempts": 5}));
+    assert_eq!(RetryPolicy::resolve(&None, &execute).unwrap().max_attempts, 5);
+    assert_eq!(RetryPolicy::resolve(&Some(json!({"max_attempts": 2})), &execute).unwrap().max_attempts, 2);
+}
@@ -0,0 +1,256 @@
+use chrono::{NaiveDate, TimeZone, Utc};
+use scheduler::{jobs::retry::retry_delay, models::fetch::RetryPolicy};
+use scheduler::{models::fetch::{ApiExecute, ExecuteType, MisfirePolicy}, utils::{calendar::parse_ics, schedule::{attempt_misfire, misfire, next_run, normalize_rrule, plan_run, validate, validate_bounds}}};
+
+fn execute(r#type: ExecuteType, rule: &str, timezone: &str) -> ApiExecute {
+    ApiExecute {
+        id: 1,
+        user_id: 1,
+        name: "schedule".to_string(),
+        is_repeat: true,
+        value: 0,
+        cron: matches!(r#type, ExecuteType::Cron).then(|| rule.to_string()),
+        rrule: matches!(r#type, ExecuteType::Rrule).then(|| rule.to_string()),
+        solar: matches!(r#type, ExecuteType::Solar).then(|| serde_json::from_str(rule).unwrap()),
+        r#type: Some(r#type),
+        timezone: Some(timezone.to_string()),
+        calendar_id: None,
+        run_at: None,
+        starts_at: None,
+        ends_at: None,
+        max_runs: None,
+        misfire_policy: MisfirePolicy::RunOnce,
+        misfire_limit: None,
+        retry: None,
+        updated_at: Utc::now(),
+    }
+}
+
+#[test]
+fn cron_weekday_in_timezone() {
+    // 08:00 Asia/Jakarta (UTC+7) on weekdays, friday 09:00 WIB -> monday 08:00 WIB
+    let execute = execute(ExecuteType::Cron, "0 0 8 * * 1-5", "Asia/Jakarta");
+    let after = Utc.with_ymd_and_hms(2026, 10, 16, 2, 0, 0).unwrap();
+
+    assert_eq!(next_run(&execute, &[], after).unwrap(), Utc.with_ymd_and_hms(2026, 10, 19, 1, 0, 0).unwrap());
+}
+
+#[test]
+fn cron_follow_dst() {
+    let execute = execute(ExecuteType::Cron, "0 9 * * *", "America/New_York");
+
+    // EST (UTC-5) before 8 march, EDT (UTC-4) after
+    let before = next_run(&execute, &[], Utc.with_ymd_and_hms(2026, 3, 7, 0, 0, 0).unwrap()).unwrap();
+    let after = next_run(&execute, &[], Utc.with_ymd_and_hms(2026, 3, 9, 0, 0, 0).unwrap()).unwrap();
+    assert_eq!(before, Utc.with_ymd_and_hms(2026, 3, 7, 14, 0, 0).unwrap());
+    assert_eq!(after, Utc.with_ymd_and_hms(2026, 3, 9, 13, 0, 0).unwrap());
+}
+
+#[test]
+fn validate_cron_execute() {
+    let cron = Some(ExecuteType::Cron);
+
+    assert!(validate(&cron, &Some("*/15 * * * * *".to_string()), &None, &None, &Some("Asia/Jakarta".to_string())).is_ok());
+    assert!(validate(&cron, &None, &None, &None, &None).is_err());
+    assert!(validate(&cron, &Some("0 61 * * *".to_string()), &None, &None, &None).is_err());
+    assert!(validate(&Some(ExecuteType::Minutes), &None, &None, &None, &Some("Mars/Olympus".to_string())).is_err());
+}
+
+#[test]
+fn rrule_last_business_day() {
+    let now = Utc.with_ymd_and_hms(2026, 10, 18, 3, 0, 0).unwrap();
+    let timezone = Some("Asia/Jakarta".to_string());
+    let rrule = normalize_rrule("FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1;BYHOUR=17;BYMINUTE=0;BYSECOND=0", &timezone, now).unwrap();
+    assert!(rrule.starts_with("DTSTART;TZID=Asia/Jakarta:20261018T000000\nRRULE:"));
+
+    // Friday 30 october 17:00 WIB
+    let execute = execute(ExecuteType::Rrule, &rrule, "Asia/Jakarta");
+    assert_eq!(next_run(&execute, &[], now).unwrap(), Utc.with_ymd_and_hms(2026, 10, 30, 10, 0, 0).unwrap());
+}
+
+#[test]
+fn rrule_exdate() {
+    // Second tuesday, november excluded
+    let rrule = "DTSTART;TZID=Asia/Jakarta:20261001T090000\nRRULE:FREQ=MONTHLY;BYDAY=2TU\nEXDATE;TZID=Asia/Jakarta:20261110T090000";
+    let execute = execute(ExecuteType::Rrule, rrule, "Asia/Jakarta");
+    let after = Utc.with_ymd_and_hms(2026, 10, 18, 0, 0, 0).unwrap();
+
+    assert_eq!(next_run(&execute, &[], after).unwrap(), Utc.with_ymd_and_hms(2026, 12, 8, 2, 0, 0).unwrap());
+}
+
+#[test]
+fn holiday_calendar_skip() {
+    let ics = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nSUMMARY:Christmas\r\nDTSTART;VALUE=DATE:20251225\r\nRRULE:FREQ=YEARLY\r\nEND:VEVENT\r\n\
+        BEGIN:VEVENT\r\nSUMMARY:Cuti\r\n bersama\r\nDTSTART;VALUE=DATE:20261228\r\nDTEND;VALUE=DATE:20261230\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+    let holidays = parse_ics(ics).unwrap();
+    let date = |d| NaiveDate::from_ymd_opt(2026, 12, d).unwrap();
+    assert!(holidays.contains(&date(25)) && holidays.contains(&date(28)) && holidays.contains(&date(29)));
+    assert!(!holidays.contains(&date(30)));
+    assert!(parse_ics("BEGIN:VCALENDAR\nEND:VCALENDAR").is_err());
+    let limited = parse_ics("BEGIN:VEVENT\nDTSTART;VALUE=DATE:20250101\nRRULE:FREQ=YEARLY;UNTIL=20270101\nEND:VEVENT").unwrap();
+    assert_eq!(limited.len(), 3);
+
+    // 25 december (friday) skipped to monday 28 -> still holiday until 30
+    let execute = execute(ExecuteType::Cron, "0 8 * * 1-5", "Asia/Jakarta");
+    let after = Utc.with_ymd_and_hms(2026, 12, 24, 2, 0, 0).unwrap();
+    assert_eq!(next_run(&execute, &holidays, after).unwrap(), Utc.with_ymd_and_hms(2026, 12, 30, 1, 0, 0).unwrap());
+}
+
+#[test]
+fn solar_before_maghrib() {
+    // Jakarta 1 march 2026 maghrib ~18:11 WIB, reminder 10 minutes before
+    let solar = r#"{"event": "maghrib", "latitude": -6.2088, "longitude": 106.8456, "method": "kemenag", "offset_minutes": -10}"#;
+    let execute = execute(ExecuteType::Solar, solar, "Asia/Jakarta");
+    let after = Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap();
+
+    let next = next_run(&execute, &[], after).unwrap();
+    let expected = Utc.with_ymd_and_hms(2026, 3, 1, 11, 1, 0).unwrap();
+    assert!((next - expected).num_seconds().abs() <= 60, "{}", next);
+
+    // Right after -> next day
+    let tomorrow = next_run(&execute, &[], next).unwrap();
+    assert_eq!(tomorrow.date_naive(), NaiveDate::from_ymd_opt(2026, 3, 2).unwrap());
+}
+
+#[test]
+fn validate_solar_execute() {
+    let solar = Some(ExecuteType::Solar);
+    let settings = |s: &str| Some(serde_json::from_str(s).unwrap());
+
+    assert!(validate(&solar, &None, &None, &settings(r#"{"event": "fajr", "latitude": 21.4, "longitude": 39.8, "method": "makkah"}"#), &None).is_ok());
+    assert!(validate(&solar, &None, &None, &None, &None).is_err());
+    assert!(validate(&solar, &None, &None, &settings(r#"{"event": "noon", "latitude": 0, "longitude": 0}"#), &None).is_err());
+    assert!(validate(&solar, &None, &None, &settings(r#"{"event": "isha", "latitude": 95, "longitude": 0}"#), &None).is_err());
+}
+
+#[test]
+fn plan_run_bounds() {
+    let now = Utc.with_ymd_and_hms(2026, 10, 18, 0, 0, 0).unwrap();
+    let run_at = Utc.with_ymd_and_hms(2026, 12, 31, 23, 59, 0).unwrap();
+
+    // One-shot at absolute time, nothing after first run
+    let mut once = execute(ExecuteType::Minutes, "", "UTC");
+    once.is_repeat = false;
+    once.run_at = Some(run_at);
+    assert_eq!(plan_run(&once, &[], 0, None, now).unwrap(), Some(run_at));
+    assert_eq!(plan_run(&once, &[], 1, None, run_at).unwrap(), None);
+
+    // Every 5 minutes inside window, at most 100 runs
+    let mut window = execute(ExecuteType::Minutes, "", "UTC");
+    window.value = 5;
+    window.starts_at = Some(Utc.with_ymd_and_hms(2026, 11, 1, 0, 0, 0).unwrap());
+    window.ends_at = Some(Utc.with_ymd_and_hms(2026, 11, 2, 0, 0, 0).unwrap());
+    window.max_runs = Some(100);
+    assert_eq!(plan_run(&window, &[], 0, None, now).unwrap(), window.starts_at);
+    let inside = Utc.with_ymd_and_hms(2026, 11, 1, 1, 0, 0).unwrap();
+    assert_eq!(plan_run(&window, &[], 12, None, inside).unwrap(), Some(inside + chrono::Duration::minutes(5)));
+    assert_eq!(plan_run(&window, &[], 100, None, inside).unwrap(), None);
+    assert_eq!(plan_run(&window, &[], 50, None, Utc.with_ymd_and_hms(2026, 11, 1, 23, 58, 0).unwrap()).unwrap(), None);
+
+    assert!(validate_bounds(&Some(run_at), &window.starts_at, &window.ends_at, &Some(100), now).is_ok());
+    assert!(validate_bounds(&Some(now), &None, &None, &None, now).is_err());
+    assert!(validate_bounds(&None, &window.ends_at, &window.starts_at, &None, now).is_err());
+    assert!(validate_bounds(&None, &None, &None, &Some(0), now).is_err());
+}
+
+#[test]
+fn plan_run_fixed_rate() {
+    let mut every = execute(ExecuteType::Minutes, "", "");
+    every.value = 5;
+    let previous = Utc.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap();
+    // Run took 40s, next slot still on the grid
+    let now = previous + chrono::Duration::seconds(40);
+    assert_eq!(plan_run(&every, &[], 1, Some(previous), now).unwrap(), Some(previous + chrono::Duration::minutes(5)));
+    // Late slot returned as is (run immediately)
+    let late = previous + chrono::Duration::minutes(7);
+    assert_eq!(plan_run(&every, &[], 1, Some(previous), late).unwrap(), Some(previous + chrono::Duration::minutes(5)));
+}
+
+#[test]
+fn misfire_policies() {
+    let mut every = execute(ExecuteType::Minutes, "", "");
+    every.value = 5;
+    let planned = Utc.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap();
+    let slot = |n: i64| planned + chrono::Duration::minutes(5 * n);
+
+    // On time, or delay below one slot
+    let on_time = misfire(&every, &[], planned, planned + chrono::Duration::minutes(2)).unwrap();
+    assert!(on_time.run && on_time.missed == 0 && on_time.previous == planned);
+
+    // Worker down 21 minutes: slots 1..4 passed
+    let now = planned + chrono::Duration::minutes(21);
+    every.misfire_policy = MisfirePolicy::Skip;
+    let skip = misfire(&every, &[], planned, now).unwrap();
+    assert!(!skip.run);
+    assert_eq!((skip.missed, skip.previous), (5, slot(4)));
+
+    every.misfire_policy = MisfirePolicy::RunOnce;
+    let once = misfire(&every, &[], planned, now).unwrap();
+    assert!(once.run);
+    assert_eq!((once.missed, once.previous), (4, slot(4)));
+
+    every.misfire_policy = MisfirePolicy::RunAll;
+    let all = misfire(&every, &[], planned, now).unwrap();
+    assert_eq!((all.missed, all.previous), (0, planned));
+    every.misfire_limit = Some(2);
+    let limited = misfire(&every, &[], planned, now).unwrap();
+    assert!(limited.run);
+    assert_eq!((limited.missed, limited.previous), (3, slot(3)));
+}
+
+#[test]
+fn misfire_with_retry() {
+    let mut every = execute(ExecuteType::Minutes, "", "");
+    every.value = 5;
+    let planned = Utc.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap();
+    let slot = |n: i64| planned + chrono::Duration::minutes(5 * n);
+    let policy = RetryPolicy { delay_seconds: 120, jitter: false, ..RetryPolicy::default() };
+
+    // First attempt 11 minutes late: run once for slot 2, kept as planned time of the job
+    let first = planned + chrono::Duration::minutes(11);
+    let once = attempt_misfire(&every, &[], 1, planned, first).unwrap();
+    assert!(once.run);
+    assert_eq!((once.missed, once.previous), (2, slot(2)));
+
+    // Retries pass more slots while backing off, no new misfire
+    let second = first + retry_delay(&policy, 1, None, 0.0);
+    let third = second + retry_delay(&policy, 2, None, 0.0);
+    assert_eq!(third, slot(3) + chrono::Duration::minutes(2));
+    for (attempt, now) in [(2, second), (3, third)] {
+        let retry = attempt_misfire(&every, &[], attempt, once.previous, now).unwrap();
+        assert!(retry.run && retry.missed == 0 && retry.previous == slot(2));
+    }
+
+    // Next job follow the slot of the first attempt, already passed so run immediately
+    assert_eq!(plan_run(&every, &[], 1, Some(once.previous), third).unwrap(), Some(slot(3)));
+    // Same job checked again as a first attempt would have skipped it
+    every.misfire_policy = MisfirePolicy::Skip;
+    assert!(!attempt_misfire(&every, &[], 1, slot(2), third).unwrap().run);
+    assert!(attempt_misfire(&every, &[], 3, slot(2), third).unwrap().run);
+}
+
+#[test]
+fn rrule_end_of_recurrence() {
+    let last = Utc.with_ymd_and_hms(2026, 3, 3, 9, 0, 0).unwrap();
+    let after_last = last + chrono::Duration::seconds(10);
+    for rule in ["DTSTART:20260301T090000Z\nRRULE:FREQ=DAILY;COUNT=3", "DTSTART:20260301T090000Z\nRRULE:FREQ=DAILY;UNTIL=20260303T090000Z"] {
+        let mut daily = execute(ExecuteType::Rrule, rule, "UTC");
+        let second = Utc.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap();
+
+        // Last occurrence planned, then schedule finished like ends_at / max_runs
+        assert_eq!(plan_run(&daily, &[], 1, Some(second), second).unwrap(), Some(last));
+        assert_eq!(plan_run(&daily, &[], 2, Some(last), after_last).unwrap(), None);
+        assert_eq!(plan_run(&daily, &[], 0, None, after_last).unwrap(), None);
+        assert!(next_run(&daily, &[], last).is_err());
+
+        // Misfire counts slots up to the end of rule
+        let late = Utc.with_ymd_and_hms(2026, 3, 10, 0, 0, 0).unwrap();
+        let final_run = misfire(&daily, &[], last, late).unwrap();
+        assert!(final_run.run && final_run.missed == 0 && final_run.previous == last);
+        daily.misfire_policy = MisfirePolicy::RunOnce;
+        let once = attempt_misfire(&daily, &[], 1, second, late).unwrap();
+        assert!(once.run);
+        assert_eq!((once.missed, once.previous), (1, last));
+        assert_eq!(plan_run(&daily, &[], 2, Some(once.previous), late).unwrap(), None);
+    }
+}
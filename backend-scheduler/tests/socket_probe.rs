@@ -0,0 +1,35 @@
+use scheduler::{jobs::socket::{SocketJobs, decode_payload}, models::fetch::PayloadEncoding};
+use serde_json::json;
+use std::time::{Duration, Instant};
+use tokio::net::TcpListener;
+
+#[test]
+fn decode_hex_payload() {
+    let hex = |payload: &str| decode_payload(&Some(payload.to_string()), &PayloadEncoding::Hex);
+    assert_eq!(hex("de ad\nbe ef").unwrap(), vec![0xde, 0xad, 0xbe, 0xef]);
+    assert!(hex("+f").is_err());
+    assert!(hex("-1").is_err());
+    assert!(hex("abc").is_err());
+    assert!(hex("zz").is_err());
+}
+
+#[tokio::test]
+async fn tcp_probe_within_one_deadline() {
+    // Peer accepts but never reads, send stalls once socket buffers are full
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        drop(stream);
+    });
+
+    let payload = Some("x".repeat(64 * 1024 * 1024));
+    let started = Instant::now();
+    let result = SocketJobs::new(10)
+        .tcp_request_response(&format!("tcp://{}", address), &payload, &Some(json!({"timeout": 1})))
+        .await;
+    assert_eq!(result.unwrap_err(), "Failed send message: timeout");
+    assert!(started.elapsed() < Duration::from_secs(3));
+    server.abort();
+}
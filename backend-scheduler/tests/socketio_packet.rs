@@ -0,0 +1,39 @@
+use scheduler::jobs::socketio::{Packet, connect_packet, event_packet, parse_packet, socketio_url};
+use serde_json::json;
+
+#[test]
+fn encode_decode_packets() {
+    assert_eq!(
+        socketio_url("https://example.com", &None).unwrap(),
+        "wss://example.com/socket.io/?EIO=4&transport=websocket"
+    );
+    assert_eq!(
+        socketio_url("https://example.com/api", &None).unwrap(),
+        "wss://example.com/api?EIO=4&transport=websocket"
+    );
+    assert_eq!(
+        socketio_url("https://example.com/api", &Some("/live/".to_string())).unwrap(),
+        "wss://example.com/live/?EIO=4&transport=websocket"
+    );
+    assert_eq!(parse_packet(r#"0{"sid":"abc","pingInterval":25000}"#), Packet::Open(json!({"sid":"abc","pingInterval":25000})));
+    assert_eq!(parse_packet("2"), Packet::Ping);
+    assert_eq!(
+        parse_packet(r#"42/market,7["tick",{"p":1},2]"#),
+        Packet::Event { namespace: "/market".to_string(), id: Some(7), event: "tick".to_string(), args: vec![json!({"p":1}), json!(2)] }
+    );
+    assert_eq!(
+        parse_packet(r#"44/market,{"message":"bad token"}"#),
+        Packet::ConnectError { namespace: "/market".to_string(), data: json!({"message":"bad token"}) }
+    );
+
+    assert_eq!(connect_packet("/", &None), "40");
+    assert_eq!(connect_packet("/market", &Some(json!({"token":"t1"}))), r#"40/market,{"token":"t1"}"#);
+    assert_eq!(event_packet("/", "subscribe", &[json!("btc")]), r#"42["subscribe","btc"]"#);
+}
+
+#[test]
+fn non_ascii_packet_type() {
+    assert_eq!(parse_packet("4é"), Packet::Other("é".to_string()));
+    assert_eq!(parse_packet("4é[1]"), Packet::Other("é[1]".to_string()));
+    assert_eq!(parse_packet("é"), Packet::Other("é".to_string()));
+}
@@ -0,0 +1,62 @@
+use scheduler::jobs::{http::{DEFAULT_MAX_BODY_BYTES, HttpClients}, sse::{SseJobs, SseParser}};
+use serde_json::json;
+use tokio::{io::{AsyncReadExt, AsyncWriteExt}, net::TcpListener};
+
+#[test]
+fn parse_event_stream_chunks() {
+    let mut parser = SseParser::new(Some("9".to_string()));
+    // Event without id line carries the resumed id
+    let resumed = parser.feed(b"data: hello\n\n");
+    assert_eq!(resumed[0]["id"], "9");
+
+    // Event split across chunks, CRLF line endings
+    let first = parser.feed(b": keep-alive\r\nid: 10\r\nevent: price\r\ndata: {\"a\":1}\r");
+    assert!(first.is_empty());
+
+    let events = parser.feed(b"\ndata: line2\r\n\r\ndata: plain\n\n");
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0]["id"], "10");
+    assert_eq!(events[0]["event"], "price");
+    assert_eq!(events[0]["data"], "{\"a\":1}\nline2");
+    assert_eq!(events[1]["id"], "10");
+    assert_eq!(events[1]["event"], "message");
+    assert_eq!(parser.last_event_id.as_deref(), Some("10"));
+}
+
+#[tokio::test]
+async fn listen_on_shared_client() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        // Endless stream, then plain error page
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut buf = [0u8; 4096];
+        let _ = stream.read(&mut buf).await.unwrap();
+        stream.write_all(b"HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\n\r\n").await.unwrap();
+        for i in 0..50 {
+            if stream.write_all(format!("id: {}\ndata: tick\n\n", i).as_bytes()).await.is_err() {
+                break;
+            }
+        }
+
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let _ = stream.read(&mut buf).await.unwrap();
+        stream.write_all(b"HTTP/1.1 503 Service Unavailable\r\ncontent-type: text/plain\r\ncontent-length: 11\r\n\r\nunavailable").await.unwrap();
+    });
+
+    let clients = HttpClients::new(5, DEFAULT_MAX_BODY_BYTES);
+    let sse = SseJobs::new(5);
+    let url = format!("http://127.0.0.1:{}/events", port);
+    let options = Some(json!({ "max_body_bytes": 40 }));
+
+    // Stopped at the byte cap, not at the window end
+    let capped = sse.request_response(&clients, &url, &None, &None, &options, None, &None, &None).await.unwrap();
+    let meta = capped.result.meta.unwrap();
+    assert_eq!(meta["body"]["truncated"], json!(true));
+    assert!(capped.last_event_id.is_some());
+
+    let failed = sse.request_response(&clients, &url, &None, &None, &options, None, &None, &Some("7".to_string())).await.unwrap();
+    assert_eq!(failed.result.status_code, 503);
+    assert_eq!(failed.result.response, "unavailable");
+    assert_eq!(failed.last_event_id.as_deref(), Some("7"));
+}
@@ -0,0 +1,14 @@
+use scheduler::models::fetch::{MAX_WS_EXPECT_TIMEOUT, MAX_WS_SLEEP_MS, WsOptions};
+use serde_json::json;
+
+#[test]
+fn bounded_script_steps() {
+    let steps = |step: serde_json::Value| WsOptions::parse(&Some(json!({"steps": [{"action": "send", "message": "hi"}, step]})));
+
+    assert!(steps(json!({"action": "sleep", "ms": MAX_WS_SLEEP_MS})).is_ok());
+    assert!(steps(json!({"action": "sleep", "ms": MAX_WS_SLEEP_MS + 1})).is_err());
+    assert!(steps(json!({"action": "expect", "pattern": "ok", "timeout": MAX_WS_EXPECT_TIMEOUT})).is_ok());
+    assert!(steps(json!({"action": "expect", "pattern": "ok", "timeout": MAX_WS_EXPECT_TIMEOUT + 1})).is_err());
+    assert!(steps(json!({"action": "expect", "pattern": "ok", "timeout": 0})).is_err());
+    assert!(steps(json!({"action": "expect", "pattern": "("})).is_err());
+}